@@ -22,6 +22,9 @@ pub enum ClientAction {
 
     /// Indicates that a client can be closed.
     Close,
+
+    /// Indicates that a client can be minimized.
+    Minimize,
 }
 
 impl ClientAction {
@@ -39,6 +42,7 @@ impl ClientAction {
             ClientAction::Resize => vec![conn.WM_ACTION_RESIZE()],
             ClientAction::Close => vec![conn.WM_ACTION_CLOSE()],
             ClientAction::Move => vec![conn.WM_ACTION_MOVE()],
+            ClientAction::Minimize => vec![conn.WM_ACTION_MINIMIZE()],
         }
     }
 }
@@ -59,6 +63,8 @@ impl Client {
             return
         }
 
+        self.allowed_actions.push(action);
+
         let new_net_allowed_actions: Vec<u32> = self.allowed_actions
             .iter()
             .flat_map(|s| s._net_wm_allowed_actions(conn))
@@ -1,3 +1,5 @@
+use crate::util::Direction;
+
 #[derive(Clone)]
 pub struct ClientGeometry {
     pub x: u32,
@@ -6,4 +8,53 @@ pub struct ClientGeometry {
     pub h: u32,
     pub border: u32,
     pub paddings: [u32; 4],
+
+    /// The `(start, end)` range of each padding along its edge, following the same order as
+    /// `paddings`. A `(0, 0)` range spans the entire edge.
+    pub strut_ranges: [(u32, u32); 4],
+
+    /// The minimum `(width, height)` of the client, from its `WM_NORMAL_HINTS`. A dimension of 0
+    /// means there is no minimum.
+    pub min_size: (u32, u32),
+
+    /// The maximum `(width, height)` of the client, from its `WM_NORMAL_HINTS`. A dimension of 0
+    /// means there is no maximum.
+    pub max_size: (u32, u32),
+}
+
+impl ClientGeometry {
+    /// Clamps the size `(w, h)` to the minimum and maximum sizes of the client.
+    pub fn clamp_size(&self, w: u32, h: u32) -> (u32, u32) {
+        let clamp = |v: u32, min: u32, max: u32| {
+            let v = v.max(min);
+            if max > 0 { v.min(max) } else { v }
+        };
+
+        (
+            clamp(w, self.min_size.0, self.max_size.0),
+            clamp(h, self.min_size.1, self.max_size.1),
+        )
+    }
+
+    /// Returns the center of the client, including its border, as a `(x, y)` tuple.
+    pub fn center(&self) -> (i64, i64) {
+        (
+            (self.x + (self.w + self.border * 2) / 2) as i64,
+            (self.y + (self.h + self.border * 2) / 2) as i64,
+        )
+    }
+
+    /// Verifies if the center of `other` is placed beyond the edge of the client in the direction
+    /// `dir`. Unlike monitors, clients may overlap (e.g. floating clients), so they are not
+    /// required to be entirely in the direction.
+    pub fn is_in_direction(&self, dir: Direction, other: &ClientGeometry) -> bool {
+        let (x, y) = other.center();
+
+        match dir {
+            Direction::Left => x < self.x as i64,
+            Direction::Right => x > (self.x + self.w + self.border * 2) as i64,
+            Direction::Up => y < self.y as i64,
+            Direction::Down => y > (self.y + self.h + self.border * 2) as i64,
+        }
+    }
 }
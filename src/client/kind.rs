@@ -2,7 +2,7 @@ use core::fmt;
 
 use xcb_util::ewmh;
 
-use crate::client::{Client, ClientID};
+use crate::client::{Client, ClientAction, ClientID, ClientState};
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum ClientType {
@@ -10,6 +10,7 @@ pub enum ClientType {
     Dock,
     Dialog,
     Splash,
+    Desktop,
 }
 
 impl fmt::Display for ClientType {
@@ -19,6 +20,7 @@ impl fmt::Display for ClientType {
             Self::Dock => write!(f, "Dock"),
             Self::Dialog => write!(f, "Dialog"),
             Self::Splash => write!(f, "Splash"),
+            Self::Desktop => write!(f, "Desktop"),
         }
     }
 }
@@ -41,6 +43,7 @@ impl ClientType {
                         t if t == conn.WM_WINDOW_TYPE_DOCK() => Some(Self::Dock),
                         t if t == conn.WM_WINDOW_TYPE_SPLASH() => Some(Self::Splash),
                         t if t == conn.WM_WINDOW_TYPE_NORMAL() => Some(Self::Normal),
+                        t if t == conn.WM_WINDOW_TYPE_DESKTOP() => Some(Self::Desktop),
                         _ => None,
                     }
                 },
@@ -54,5 +57,35 @@ impl Client {
     pub fn preferable_type(&self) -> Option<ClientType> {
         self.types.get(0).cloned()
     }
+
+    /// Verifies if the client is a dock, i.e. its most preferable type is `ClientType::Dock`.
+    pub fn is_dock(&self) -> bool {
+        self.preferable_type().is_some_and(|t| t == ClientType::Dock)
+    }
+
+    /// Applies the behavior defined by the client's type. Docks are sticky and not controlled by
+    /// the window manager, while the other clients are controlled and may be moved, resized, etc.
+    /// Must be called again when the client becomes or stops being a dock.
+    pub fn classify(&mut self, conn: &ewmh::Connection) {
+        if self.is_dock() {
+            self.is_controlled = false;
+            self.add_state(conn, ClientState::Sticky);
+            return
+        }
+
+        self.is_controlled = true;
+        self.remove_state(conn, ClientState::Sticky);
+        self.allow_actions(
+            conn,
+            vec![
+                ClientAction::Maximize,
+                ClientAction::Fullscreen,
+                ClientAction::ChangeTag,
+                ClientAction::Resize,
+                ClientAction::Move,
+                ClientAction::Minimize,
+            ],
+        );
+    }
 }
 
@@ -0,0 +1,49 @@
+use crate::client::{Client, ClientState, ClientType};
+
+/// Represents the stacking layer of a client. Clients of a higher layer are always stacked above
+/// the clients of a lower layer; within a layer, the stacking order is kept by the screen.
+///
+/// Refer to: https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#STACKINGORDER
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum StackLayer {
+    /// Clients with the `_NET_WM_WINDOW_TYPE_DESKTOP` type.
+    Desktop,
+
+    /// Clients with the `_NET_WM_STATE_BELOW` state.
+    Below,
+
+    /// Clients arranged by the layout, as well as maximized clients.
+    Normal,
+
+    /// Clients with the `ClientState::Floating` state, including sticky clients other than docks
+    /// (e.g. picture-in-picture windows), which always float.
+    Floating,
+
+    /// Clients with the `_NET_WM_STATE_ABOVE` state.
+    Above,
+
+    /// Clients with the `_NET_WM_WINDOW_TYPE_DOCK` type.
+    Dock,
+
+    /// Clients with the `_NET_WM_STATE_FULLSCREEN` state.
+    Fullscreen,
+}
+
+impl Client {
+    /// Returns the stacking layer of the client, which depends on its type and states.
+    pub fn layer(&self) -> StackLayer {
+        match self.preferable_type() {
+            Some(ClientType::Desktop) => return StackLayer::Desktop,
+            Some(ClientType::Dock) => return StackLayer::Dock,
+            _ => (),
+        };
+
+        match self.get_state() {
+            ClientState::Fullscreen => StackLayer::Fullscreen,
+            _ if self.has_state(&ClientState::Above) => StackLayer::Above,
+            _ if self.has_state(&ClientState::Below) => StackLayer::Below,
+            ClientState::Floating | ClientState::Sticky => StackLayer::Floating,
+            _ => StackLayer::Normal,
+        }
+    }
+}
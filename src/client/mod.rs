@@ -1,19 +1,34 @@
 mod action;
 mod kind;
+mod layer;
 mod geometry;
+mod properties;
 mod state;
+mod swallow;
+mod wm_state;
+
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use xcb_util::{ewmh, icccm};
 
-use crate::util as gutil; // TODO: change this!!!!!!
+use crate::{
+    config::Config,
+    util as gutil, // TODO: change this!!!!!!
+};
 
 pub use crate::client::{
     action::ClientAction,
     kind::ClientType,
+    layer::StackLayer,
     geometry::ClientGeometry,
     state::ClientState,
+    wm_state::WmState,
 };
 
+/// Source of `Client::last_focused` stamps, shared by all clients so the focus history can be
+/// ordered across tags.
+static FOCUS_STAMP: AtomicU64 = AtomicU64::new(1);
+
 /// Represents the ID of the client. Typically the `event.window()`, `event.child()` or
 /// `event.event()` in XCB events.
 pub type ClientID = u32;
@@ -27,16 +42,34 @@ pub struct Client {
     /// The `_NET_WM_PID` of the client, also known as the process ID.
     pub wm_pid: Option<u32>,
 
-    /// The `WM_CLASS` of the client.
+    /// The class of the client, i.e. the second part of its `WM_CLASS`.
     pub wm_class: Option<String>,
 
+    /// The instance of the client, i.e. the first part of its `WM_CLASS`.
+    pub wm_instance: Option<String>,
+
     /// The `WM_NAME` of the client.
     pub wm_name: Option<String>,
 
     pub geo: ClientGeometry,
 
+    /// Increasing stamp of the last time the client was focused, used to order the focus history
+    /// (most recently used first). It is 0 when the client was never focused. See
+    /// `Client::mark_focused`.
+    pub last_focused: u64,
+
+    /// Bitmask of the additional tags on which the client is shown, besides the tag that manages
+    /// it. Bit `i` is the tag at position `i` of the client's monitor, as in `Monitor::view_mask`.
+    /// EWMH has no notion of clients on several desktops, so `_NET_WM_DESKTOP` keeps the tag that
+    /// manages the client.
+    pub tag_mask: u32,
+
     is_controlled: bool,
 
+    /// Number of `UnmapNotify` events caused by the window manager (e.g. when hiding a tag) that
+    /// were not received yet. These events must not be treated as the client withdrawing itself.
+    pending_unmaps: u32,
+
     /// Represents the list of types associated with a client. Each type must be unique in the vector.
     /// Typically, a client has a single unique type. However, in cases where a client has multiple types,
     /// the first one is considered the most preferable.
@@ -66,6 +99,21 @@ pub struct Client {
     allowed_actions: Vec<ClientAction>,
 
     protocols: Vec<u32>,
+
+    /// Indicates if the client relies on the window manager to receive the input focus, which is
+    /// the `input` field of its `WM_HINTS`. Clients without `WM_HINTS` are assumed to accept it.
+    ///
+    /// Together with the `WM_TAKE_FOCUS` protocol, it defines the ICCCM input model of the client.
+    /// Refer to: https://tronche.com/gui/x/icccm/sec-4.html#s-4.1.7
+    accepts_input: bool,
+
+    /// Indicates if the urgency hint of the client's `WM_HINTS` is set. It is kept apart from
+    /// `ClientState::DemandsAttention`, which may also be requested through `_NET_WM_STATE`.
+    urgency_hint: bool,
+
+    /// The client replaced by this client in the layout, usually the terminal from which it was
+    /// started. See `Client::can_swallow`.
+    swallowed: Option<Box<Client>>,
 }
 
 impl Client {
@@ -73,13 +121,20 @@ impl Client {
         let mut client = Self {
             id,
             is_controlled: false,
+            pending_unmaps: 0,
             states: vec![ClientState::Tile],
             allowed_actions: vec![],
             types: vec![],
             protocols: vec![],
+            accepts_input: true,
+            urgency_hint: false,
+            swallowed: None,
             wm_class: None,
+            wm_instance: None,
             wm_pid: None,
             wm_name: None,
+            last_focused: 0,
+            tag_mask: 0,
             geo: ClientGeometry {
                 x: 0,
                 y: 0,
@@ -87,27 +142,37 @@ impl Client {
                 h: 0,
                 border: 0,
                 paddings: [0, 0, 0, 0],
+                strut_ranges: [(0, 0); 4],
+                min_size: (0, 0),
+                max_size: (0, 0),
             },
         };
 
+        // Property changes are used to keep the client up to date; see `handlers::on_property_notify`.
+        // Entering the client triggers `MouseEvent::Enter`.
+        xcb::change_window_attributes(
+            conn,
+            id,
+            &[(xcb::CW_EVENT_MASK, xcb::EVENT_MASK_PROPERTY_CHANGE | xcb::EVENT_MASK_ENTER_WINDOW)],
+        );
+
         if let Ok(r) = icccm::get_wm_class(conn, id).get_reply() {
             client.wm_class = Some(r.class().to_owned());
-        }
-
-        if let Ok(r) = icccm::get_wm_name(conn, id).get_reply() {
-            client.wm_name = Some(r.name().to_owned());
+            client.wm_instance = Some(r.instance().to_owned());
         }
 
         if let Ok(p) = ewmh::get_wm_pid(conn, id).get_reply() {
             client.wm_pid = Some(p);
         }
 
-        if let Ok(s) = ewmh::get_wm_strut_partial(conn, id).get_reply() {
-            client.geo.paddings[0] = s.top;
-            client.geo.paddings[1] = s.bottom;
-            client.geo.paddings[2] = s.left;
-            client.geo.paddings[3] = s.right;
-        };
+        client.update_name(conn);
+        client.update_struts(conn);
+        client.update_size_hints(conn);
+
+        // The urgency hint of the ICCCM is treated like "_NET_WM_STATE_DEMANDS_ATTENTION".
+        if client.update_hints(conn) == Some(true) {
+            client.add_state(conn, ClientState::DemandsAttention);
+        }
 
         // TODO: maybe a custom enum with the supported protocols?
         client.protocols = xcb_util::icccm::get_wm_protocols(conn, id, conn.WM_PROTOCOLS())
@@ -117,36 +182,61 @@ impl Client {
                 |p| p.atoms().to_vec(),
             );
 
-        client.types = ClientType::from_atoms(conn, id);
+        client.update_types(conn);
         client.allow_action(conn, ClientAction::Close);
-
-        if client.preferable_type().is_some_and(|t| t == ClientType::Dock) {
-            client.add_state(conn, ClientState::Sticky);
-        } else {
-            client.is_controlled = true;
-            client.allow_actions(
-                conn,
-                vec![
-                    ClientAction::Maximize,
-                    ClientAction::Fullscreen,
-                    ClientAction::ChangeTag,
-                    ClientAction::Resize,
-                    ClientAction::Move,
-                ],
-            );
-        }
+        client.classify(conn);
 
         client
     }
 
-    /// Maps a window.
+    /// Maps a window and sets its `WM_STATE` to `WmState::Normal`.
     pub fn map(&self, conn: &ewmh::Connection) {
         xcb::map_window(conn, self.id);
+        self.set_wm_state(conn, WmState::Normal);
     }
 
-    /// Unmaps a window.
-    pub fn unmap(&self, conn: &ewmh::Connection) {
+    /// Unmaps a window and sets its `WM_STATE` to `WmState::Iconic`, as the client is still
+    /// managed. The resulting `UnmapNotify` is expected; see `Client::consume_unmap`.
+    pub fn unmap(&mut self, conn: &ewmh::Connection) {
+        self.pending_unmaps += 1;
+
         xcb::unmap_window(conn, self.id);
+        self.set_wm_state(conn, WmState::Iconic);
+    }
+
+    /// Verifies if an `UnmapNotify` of the client was caused by the window manager, consuming it.
+    /// When it returns `false`, the client has withdrawn itself and must be unmanaged.
+    pub fn consume_unmap(&mut self) -> bool {
+        if self.pending_unmaps == 0 {
+            return false
+        }
+
+        self.pending_unmaps -= 1;
+        true
+    }
+
+    /// Marks the client as withdrawn, removing the properties that the window manager maintains
+    /// on managed clients. Must be called when a client that still exists is unmanaged.
+    pub fn withdraw(&self, conn: &ewmh::Connection) {
+        self.set_wm_state(conn, WmState::Withdrawn);
+
+        xcb::delete_property(conn, self.id, conn.WM_STATE());
+        xcb::delete_property(conn, self.id, conn.WM_DESKTOP());
+    }
+
+    /// Applies the geometry of the client (position, size and border width) to its window.
+    pub fn configure(&self, conn: &ewmh::Connection) {
+        xcb::configure_window(
+            conn,
+            self.id,
+            &[
+                (xcb::CONFIG_WINDOW_WIDTH as u16, self.geo.w),
+                (xcb::CONFIG_WINDOW_HEIGHT as u16, self.geo.h),
+                (xcb::CONFIG_WINDOW_X as u16, self.geo.x),
+                (xcb::CONFIG_WINDOW_Y as u16, self.geo.y),
+                (xcb::CONFIG_WINDOW_BORDER_WIDTH as u16, self.geo.border),
+            ],
+        );
     }
 
     pub fn set_border(&self, conn: &ewmh::Connection, color: u32) {
@@ -157,44 +247,98 @@ impl Client {
         );
     }
 
+    /// Updates the border color of the client according to its focus and urgency. Urgent clients
+    /// use `color_urgent`, even when focused.
+    pub fn update_border(&self, conn: &ewmh::Connection, focused: bool) {
+        let config = Config::current();
+
+        let color = if self.is_urgent() {
+            config.border.color_urgent
+        } else if focused {
+            config.border.color_active
+        } else {
+            config.border.color_normal
+        };
+
+        self.set_border(conn, color);
+    }
+
+    /// Makes the client the most recently focused one in the focus history.
+    pub fn mark_focused(&mut self) {
+        self.last_focused = FOCUS_STAMP.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Verifies if the client demands attention, either through "_NET_WM_STATE_DEMANDS_ATTENTION"
+    /// or through the urgency hint of its `WM_HINTS`.
+    #[inline(always)]
+    pub fn is_urgent(&self) -> bool {
+        self.has_state(&ClientState::DemandsAttention)
+    }
+
+    /// Sets the client as the input focus and updates the `_NET_ACTIVE_WINDOW` accordingly. The
+    /// ICCCM input model of the client is respected: the input focus is only set when the client
+    /// accepts it, and `WM_TAKE_FOCUS` is sent when the client supports the protocol, letting
+    /// globally active clients (e.g. Java applications) set the focus themselves. No-input clients,
+    /// which never receive the focus, are not made the `_NET_ACTIVE_WINDOW`.
     pub fn set_input_focus(&self, conn: &ewmh::Connection) {
-        xcb::set_input_focus(
-            conn,
-            xcb::INPUT_FOCUS_PARENT as u8,
-            self.id,
-            xcb::CURRENT_TIME
-        );
+        let wm_take_focus = gutil::get_atom(conn, "WM_TAKE_FOCUS");
+        let takes_focus = self.has_protocol(wm_take_focus);
+
+        if !self.accepts_input && !takes_focus {
+            return
+        }
+
+        if self.accepts_input {
+            xcb::set_input_focus(
+                conn,
+                xcb::INPUT_FOCUS_PARENT as u8,
+                self.id,
+                xcb::CURRENT_TIME
+            );
+        }
+
+        if takes_focus {
+            self.send_protocol(conn, wm_take_focus);
+        }
+
+        ewmh::set_active_window(conn, 0, self.id);
     }
 
     pub fn has_protocol(&self, atom: xcb::Atom) -> bool {
         self.protocols.contains(&atom)
     }
 
+    /// Sends a `WM_PROTOCOLS` client message with the protocol `atom` to the client. The message
+    /// carries the timestamp of the event that triggered it, as required by the ICCCM.
+    fn send_protocol(&self, conn: &ewmh::Connection, atom: xcb::Atom) {
+        let event = xcb::ClientMessageEvent::new(
+            32,
+            self.id,
+            conn.WM_PROTOCOLS(),
+            xcb::ClientMessageData::from_data32([
+                atom,
+                gutil::event_time(),
+                xcb::NONE,
+                xcb::NONE,
+                xcb::NONE,
+            ]),
+        );
+
+        xcb::send_event(
+            conn,
+            false,
+            self.id,
+            xcb::EVENT_MASK_NO_EVENT,
+            &event,
+        );
+    }
+
     pub fn kill(&self, conn: &ewmh::Connection) {
         let wm_delete_window = gutil::get_atom(conn, "WM_DELETE_WINDOW");
 
         if self.has_protocol(wm_delete_window) {
-            let event = xcb::ClientMessageEvent::new(
-                32,
-                self.id,
-                conn.WM_PROTOCOLS(),
-                xcb::ClientMessageData::from_data32([
-                    wm_delete_window,
-                    xcb::CURRENT_TIME,
-                    xcb::NONE,
-                    xcb::NONE,
-                    xcb::NONE,
-                ]),
-            );
-
             // TODO: kill with PID when this event fails
-            xcb::send_event(
-                &conn,
-                false,
-                self.id,
-                xcb::EVENT_MASK_NO_EVENT,
-                &event,
-            );
+            self.send_protocol(conn, wm_delete_window);
         } else {
             xcb::kill_client(conn, self.id);
         }
@@ -204,4 +348,11 @@ impl Client {
     pub fn is_controlled(&self) -> bool {
         self.is_controlled
     }
+
+    /// Verifies if the client can receive the focus, i.e. it is controlled by the window manager
+    /// and is not minimized.
+    #[inline(always)]
+    pub fn is_focusable(&self) -> bool {
+        self.is_controlled && !self.has_state(&ClientState::Hidden)
+    }
 }
@@ -0,0 +1,94 @@
+use xcb_util::{ewmh, icccm};
+
+use crate::client::{Client, ClientType};
+
+/// Flags of the `WM_NORMAL_HINTS` property that indicate the presence of the minimum and maximum
+/// sizes.
+/// See: https://tronche.com/gui/x/icccm/sec-4.html#s-4.1.2.3
+const SIZE_HINT_P_MIN_SIZE: u32 = 1 << 4;
+const SIZE_HINT_P_MAX_SIZE: u32 = 1 << 5;
+
+impl Client {
+    /// Reads the name of the client, preferring the UTF-8 `_NET_WM_NAME` over the ICCCM `WM_NAME`.
+    pub fn update_name(&mut self, conn: &ewmh::Connection) {
+        self.wm_name = match ewmh::get_wm_name(conn, self.id).get_reply() {
+            Ok(r) if !r.string().is_empty() => Some(r.string().to_owned()),
+            _ => icccm::get_wm_name(conn, self.id)
+                .get_reply()
+                .ok()
+                .map(|r| r.name().to_owned()),
+        };
+    }
+
+    /// Reads the space reserved by the client on the edges of the screen, from the
+    /// `_NET_WM_STRUT_PARTIAL` or, when missing, from the older `_NET_WM_STRUT`.
+    pub fn update_struts(&mut self, conn: &ewmh::Connection) {
+        if let Ok(s) = ewmh::get_wm_strut_partial(conn, self.id).get_reply() {
+            self.geo.paddings = [s.top, s.bottom, s.left, s.right];
+            self.geo.strut_ranges = [
+                (s.top_start_x, s.top_end_x),
+                (s.bottom_start_x, s.bottom_end_x),
+                (s.left_start_y, s.left_end_y),
+                (s.right_start_y, s.right_end_y),
+            ];
+        } else if let Ok(s) = ewmh::get_wm_strut(conn, self.id).get_reply() {
+            self.geo.paddings = [s.top(), s.bottom(), s.left(), s.right()];
+            self.geo.strut_ranges = [(0, 0); 4];
+        } else {
+            self.geo.paddings = [0, 0, 0, 0];
+            self.geo.strut_ranges = [(0, 0); 4];
+        }
+    }
+
+    /// Reads the `WM_HINTS` of the client, updating its input model and urgency hint. Returns the
+    /// new value of the urgency hint when it changed, otherwise `None`.
+    pub fn update_hints(&mut self, conn: &ewmh::Connection) -> Option<bool> {
+        let urgency_hint = match icccm::get_wm_hints(conn, self.id).get_reply() {
+            Ok(h) => {
+                self.accepts_input = h.input().unwrap_or(true);
+                h.is_urgent().unwrap_or(false)
+            },
+            Err(_) => {
+                self.accepts_input = true;
+                false
+            },
+        };
+
+        if urgency_hint == self.urgency_hint {
+            return None
+        }
+
+        self.urgency_hint = urgency_hint;
+        Some(urgency_hint)
+    }
+
+    /// Reads the minimum and maximum sizes of the client from its `WM_NORMAL_HINTS`.
+    ///
+    /// The property is read directly since the accessors of `icccm::SizeHints` do not check the
+    /// flags correctly.
+    pub fn update_size_hints(&mut self, conn: &ewmh::Connection) {
+        self.geo.min_size = (0, 0);
+        self.geo.max_size = (0, 0);
+
+        let reply = xcb::get_property(conn, false, self.id, xcb::ATOM_WM_NORMAL_HINTS, xcb::ATOM_WM_SIZE_HINTS, 0, 18)
+            .get_reply();
+
+        let hints = match reply {
+            Ok(ref r) if r.value_len() >= 9 => r.value::<u32>(),
+            _ => return,
+        };
+
+        if hints[0] & SIZE_HINT_P_MIN_SIZE != 0 {
+            self.geo.min_size = (hints[5], hints[6]);
+        }
+
+        if hints[0] & SIZE_HINT_P_MAX_SIZE != 0 {
+            self.geo.max_size = (hints[7], hints[8]);
+        }
+    }
+
+    /// Reads the `_NET_WM_WINDOW_TYPE` of the client.
+    pub fn update_types(&mut self, conn: &ewmh::Connection) {
+        self.types = ClientType::from_atoms(conn, self.id);
+    }
+}
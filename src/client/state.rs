@@ -1,7 +1,7 @@
 use xcb_util::ewmh;
 
 use crate::{
-    client::Client,
+    client::{Client, WmState},
     util::Operation,
     errors::Error,
 };
@@ -20,12 +20,29 @@ pub enum ClientState {
     /// just one of these atoms at a time, so we ensure that they are both seted together.
     Maximized,
 
-    /// Indicates that a client has the `WM_STATE_STICKY` atom. This state cannot be
-    /// toggled, as SapphireWM only allows docks to have it.
+    /// Indicates that a client has the `WM_STATE_STICKY` atom, being shown on every tag. Docks
+    /// always have it, while other clients (e.g. picture-in-picture windows) may toggle it; see
+    /// `Screen::set_client_sticky`.
     Sticky,
 
     /// Indicates that a client has the `_NET_WM_STATE_HIDDEN` atom. 
     Hidden,
+
+    /// Indicates that a client is not arranged by the layout and keeps its own geometry. There is
+    /// no EWMH atom for this state.
+    Floating,
+
+    /// Indicates that a client has the `_NET_WM_STATE_DEMANDS_ATTENTION` atom. This state does not
+    /// change how the client is drawn.
+    DemandsAttention,
+
+    /// Indicates that a client has the `_NET_WM_STATE_ABOVE` atom, being stacked above the other
+    /// clients. See `StackLayer`.
+    Above,
+
+    /// Indicates that a client has the `_NET_WM_STATE_BELOW` atom, being stacked below the other
+    /// clients. See `StackLayer`.
+    Below,
 }
 
 impl ClientState {
@@ -41,9 +58,19 @@ impl ClientState {
             ],
             ClientState::Sticky => vec![conn.WM_STATE_STICKY()],
             ClientState::Hidden => vec![conn.WM_STATE_HIDDEN()],
+            ClientState::DemandsAttention => vec![conn.WM_STATE_DEMANDS_ATTENTION()],
+            ClientState::Above => vec![conn.WM_STATE_ABOVE()],
+            ClientState::Below => vec![conn.WM_STATE_BELOW()],
+            ClientState::Floating => vec![],
             ClientState::Tile => vec![0], // When tiling, the client doesn't have any WM state.
         }
     }
+
+    /// Verifies if the state changes how the client is arranged. Stacking states only change the
+    /// stacking order of the client.
+    pub fn affects_layout(&self) -> bool {
+        !matches!(self, ClientState::DemandsAttention | ClientState::Above | ClientState::Below)
+    }
 }
 
 impl Client {
@@ -54,10 +81,16 @@ impl Client {
 
     /// Returns the last state of the client. As the latest pushed states have more privileges
     /// when the window manager needs to perform actions related to the client's state,
-    /// use this function to determine which client's state to handle. When the client
-    /// does not have any state, it returns `ClientState::Tile`.
+    /// use this function to determine which client's state to handle. States that do not affect
+    /// how the client is drawn are skipped. When the client does not have any state, it returns
+    /// `ClientState::Tile`.
     pub fn get_state(&self) -> ClientState {
-        self.states.last().unwrap_or(&ClientState::Tile).clone()
+        self.states
+            .iter()
+            .rev()
+            .find(|s| s.affects_layout())
+            .unwrap_or(&ClientState::Tile)
+            .clone()
     }
 
     /// Adds the specified `state` to the client's list of states if it is not already present. It
@@ -77,6 +110,11 @@ impl Client {
             state._net_wm_state(conn).as_slice(),
         );
 
+        // Hidden (minimized) clients are iconified in terms of the ICCCM.
+        if state == ClientState::Hidden {
+            self.set_wm_state(conn, WmState::Iconic);
+        }
+
         self.states.push(state);
     }
 
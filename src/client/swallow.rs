@@ -0,0 +1,96 @@
+use xcb_util::ewmh;
+
+use crate::{
+    client::{Client, ClientID, ClientState, ClientType},
+    config::Config,
+};
+
+impl Client {
+    /// Verifies if the client can swallow `other`, i.e. replace it in the layout. That is the case
+    /// when `other` is a visible client whose class is in `ConfigSwallow::swallowable` (usually a
+    /// terminal) and the client is a normal window, whose class is not in
+    /// `ConfigSwallow::never_swallow`, started by a descendant process of `other`.
+    pub fn can_swallow(&self, other: &Client) -> bool {
+        let config = Config::current();
+        let has_class = |c: &Client, classes: &[String]| {
+            c.wm_class.as_ref().is_some_and(|class| classes.contains(class))
+        };
+
+        if !has_class(other, &config.swallow.swallowable) || has_class(self, &config.swallow.never_swallow) {
+            return false
+        }
+
+        if !self.is_controlled() || self.preferable_type().is_some_and(|t| t != ClientType::Normal) {
+            return false
+        }
+
+        if other.has_state(&ClientState::Hidden) {
+            return false
+        }
+
+        match (self.wm_pid, other.wm_pid) {
+            (Some(pid), Some(ancestor)) => pid != ancestor && is_descendant(pid, ancestor),
+            _ => false,
+        }
+    }
+
+    /// Swallows `other`, which is unmapped and kept by the client until it is restored with
+    /// `Client::unswallow`. `other` must no longer be managed by any tag.
+    pub fn swallow(&mut self, conn: &ewmh::Connection, mut other: Client) {
+        other.unmap(conn);
+        self.swallowed = Some(Box::new(other));
+    }
+
+    /// Takes the client swallowed by the client, if any. The client is not mapped.
+    pub fn unswallow(&mut self) -> Option<Client> {
+        let mut other = *self.swallowed.take()?;
+
+        // The `UnmapNotify` caused by swallowing the client was ignored, as it was not managed by
+        // any tag at that time.
+        other.pending_unmaps = 0;
+
+        Some(other)
+    }
+
+    /// Drops the client with ID `id` from the clients swallowed by the client, directly or not,
+    /// e.g. when it is destroyed. Returns `true` when the client was found.
+    pub fn forget_swallowed(&mut self, id: ClientID) -> bool {
+        match self.swallowed.as_mut() {
+            Some(s) if s.id == id => {
+                let inner = s.swallowed.take();
+                self.swallowed = inner;
+                true
+            },
+            Some(s) => s.forget_swallowed(id),
+            None => false,
+        }
+    }
+}
+
+/// Verifies if the process `pid` is a descendant of the process `ancestor`, walking the parent
+/// chain through `/proc/<pid>/stat`.
+fn is_descendant(mut pid: u32, ancestor: u32) -> bool {
+    while pid > 1 {
+        pid = match get_parent_pid(pid) {
+            Some(p) => p,
+            None => return false,
+        };
+
+        if pid == ancestor {
+            return true
+        }
+    }
+
+    false
+}
+
+/// Reads the parent of the process `pid` from `/proc/<pid>/stat`. The name of the process is
+/// enclosed in parentheses and may contain spaces, so the fields are read after the last one.
+/// See: https://man7.org/linux/man-pages/man5/proc_pid_stat.5.html
+fn get_parent_pid(pid: u32) -> Option<u32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let (_, fields) = stat.rsplit_once(')')?;
+
+    // The fields after the name are the state and the parent ID.
+    fields.split_whitespace().nth(1)?.parse().ok()
+}
@@ -0,0 +1,58 @@
+use xcb_util::ewmh;
+
+use crate::{
+    client::Client,
+    util,
+};
+
+/// Represents the ICCCM `WM_STATE` of a client, which is different from the EWMH `_NET_WM_STATE`
+/// represented by `ClientState`.
+///
+/// Refer to: https://tronche.com/gui/x/icccm/sec-4.html#s-4.1.3.1
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WmState {
+    /// Indicates that the client is not managed. The `WM_STATE` property is removed.
+    Withdrawn,
+
+    /// Indicates that the client is mapped.
+    Normal,
+
+    /// Indicates that the client is managed but not visible, e.g. when its tag is hidden or when
+    /// it is minimized.
+    Iconic,
+}
+
+impl WmState {
+    /// Returns the value of the state in the `WM_STATE` property.
+    fn value(&self) -> u32 {
+        match self {
+            WmState::Withdrawn => 0,
+            WmState::Normal => 1,
+            WmState::Iconic => 3,
+        }
+    }
+}
+
+impl Client {
+    /// Updates the `WM_STATE` property of the client to `state`. The property is removed when the
+    /// state is `WmState::Withdrawn`.
+    pub fn set_wm_state(&self, conn: &ewmh::Connection, state: WmState) {
+        let wm_state = util::get_atom(conn, "WM_STATE");
+
+        if state == WmState::Withdrawn {
+            xcb::delete_property(conn, self.id, wm_state);
+            return
+        }
+
+        // The second field is the icon window, which SapphireWM does not use.
+        xcb::change_property(
+            conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            self.id,
+            wm_state,
+            wm_state,
+            32,
+            &[state.value(), xcb::NONE],
+        );
+    }
+}
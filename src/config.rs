@@ -1,4 +1,9 @@
-use std::sync::{RwLock, Arc};
+use std::{
+    str::FromStr,
+    sync::{RwLock, Arc},
+};
+
+use crate::{errors::Error, scratchpad::Scratchpad};
 
 thread_local! {
     static GLOBAL_CONFIG: RwLock<Arc<Config>> = RwLock::new(Arc::new(Config::default()))
@@ -8,6 +13,17 @@ thread_local! {
 pub struct Config {
     pub useless_gap: u32,
     pub border: ConfigBorder,
+    pub focus_stealing: FocusStealingPolicy,
+    pub scratchpads: Vec<Scratchpad>,
+    pub swallow: ConfigSwallow,
+
+    /// Warps the pointer to the center of the client focused by a keyboard action, such as moving
+    /// the focus or viewing another tag.
+    pub mouse_follows_focus: bool,
+
+    /// Viewing the focused tag again with `Screen::view_tag_back_and_forth` views the previously
+    /// focused tag instead, e.g. pressing the key of the current tag jumps back.
+    pub tag_back_and_forth: bool,
 }
 
 #[derive(Default)]
@@ -15,6 +31,54 @@ pub struct ConfigBorder {
     pub width: u32,
     pub color_active: u32,
     pub color_normal: u32,
+
+    /// Border color of clients that demand attention (urgent clients).
+    pub color_urgent: u32,
+}
+
+/// Defines which clients take part in window swallowing, where a client started from a terminal
+/// replaces the terminal in the layout until it is closed. Classes are matched against the second
+/// part of `WM_CLASS`.
+#[derive(Default)]
+pub struct ConfigSwallow {
+    /// Classes of the clients that can be swallowed, usually terminals. Swallowing is disabled
+    /// when empty.
+    pub swallowable: Vec<String>,
+
+    /// Classes of the clients that never swallow, e.g. programs that are usually started from a
+    /// terminal but must not hide it.
+    pub never_swallow: Vec<String>,
+}
+
+/// Defines how SapphireWM handles `_NET_ACTIVE_WINDOW` requests, sent when a client asks to be
+/// activated (e.g. "open link in existing browser"). Requests from pagers and taskbars are always
+/// honored, as they are the result of a user action.
+/// See: https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142911792
+#[derive(Default, Clone, PartialEq)]
+pub enum FocusStealingPolicy {
+    /// Always switches to the client's tag and focuses it.
+    #[default]
+    Always,
+
+    /// Ignores requests from applications.
+    PagerOnly,
+
+    /// Marks the client as demanding attention instead of focusing it when the request comes
+    /// from an application.
+    MarkUrgent,
+}
+
+impl FromStr for FocusStealingPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(Self::Always),
+            "pager-only" => Ok(Self::PagerOnly),
+            "mark-urgent" => Ok(Self::MarkUrgent),
+            _ => Err(Error::Custom(format!("Invalid focus stealing policy \"{}\".", s))),
+        }
+    }
 }
 
 impl Config {
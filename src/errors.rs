@@ -1,6 +1,7 @@
 use crate::{
     tag::TagID,
     client::ClientID,
+    monitor::MonitorID,
 };
 
 #[derive(Debug)]
@@ -10,6 +11,10 @@ pub enum Error {
     TagNotFound(TagID),
 
     ClientNotFound(ClientID),
+
+    MonitorNotFound(MonitorID),
+
+    ScratchpadNotFound(String),
     
     InvalidOperation,
 }
@@ -20,6 +25,8 @@ impl ToString for Error {
             Error::Custom(e) => e.to_owned(),
             Error::TagNotFound(id) => format!("Tag with ID {} not found.", id),
             Error::ClientNotFound(id ) => format!("Client with ID {} not found.", id),
+            Error::MonitorNotFound(id) => format!("Monitor with ID {} not found.", id),
+            Error::ScratchpadNotFound(name) => format!("Scratchpad \"{}\" not found.", name),
             Error::InvalidOperation => "Invalid operation".to_owned(),
         }
     }
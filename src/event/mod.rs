@@ -4,6 +4,8 @@ use std::fmt;
 
 use xcb_util::ewmh;
 
+use crate::util;
+
 pub use crate::event::context::EventContext;
 
 pub enum Event {
@@ -131,7 +133,7 @@ impl fmt::Display for Event {
 }
 
 /// Represents the events that the window manager should listen for mouse actions.
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum MouseEvent {
     /// Represents the `xcb::EVENT_MASK_BUTTON_PRESS` mask, which is globally grabbed on the `screen.root()`
     /// without any modifiers. It sends an `xcb::BUTTON_PRESS` event and is used to set focus on the window when clicked.
@@ -141,12 +143,18 @@ pub enum MouseEvent {
     /// TODO:
     /// Change the event mask to `xcb::EVENT_MASK_BUTTON_RELEASE`
     Click,
+
+    /// Represents the `xcb::ENTER_NOTIFY` event, sent when the pointer enters a client. It is used
+    /// to focus the client under the pointer (sloppy focus). Events caused by keyboard actions,
+    /// such as a client arranged below the pointer, are not triggered.
+    Enter,
 }
 
 impl fmt::Display for MouseEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             MouseEvent::Click => write!(f, "MouseClick"),
+            MouseEvent::Enter => write!(f, "MouseEnter"),
         }
     }
 }
@@ -166,6 +174,36 @@ pub enum ClientMessage {
     ///
     /// > Refer to [_NET_WM_DESKTOP](https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142858672)
     ChangeState,
+
+    /// Specifies when the SapphireWM should activate (view and focus) a client.
+    ///
+    /// > Refer to [_NET_ACTIVE_WINDOW](https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142911792)
+    ActivateWindow,
+
+    /// Specifies when the SapphireWM should move a client to another virtual desktop.
+    ///
+    /// > Refer to [_NET_WM_DESKTOP](https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142865424)
+    MoveToDesktop,
+
+    /// Specifies when the SapphireWM should close a client.
+    ///
+    /// > Refer to [_NET_CLOSE_WINDOW](https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142905008)
+    CloseWindow,
+
+    /// Specifies when the SapphireWM should move and/or resize a client.
+    ///
+    /// > Refer to [_NET_MOVERESIZE_WINDOW](https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142902656)
+    MoveResizeWindow,
+
+    /// Specifies when the SapphireWM should restack a client relative to a sibling.
+    ///
+    /// > Refer to [_NET_RESTACK_WINDOW](https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142890016)
+    RestackWindow,
+
+    /// Specifies when the SapphireWM should iconify (minimize) a client.
+    ///
+    /// > Refer to [WM_CHANGE_STATE](https://tronche.com/gui/x/icccm/sec-4.html#s-4.1.4)
+    ChangeWmState,
 }
 
 impl ClientMessage {
@@ -174,6 +212,12 @@ impl ClientMessage {
         match type_ {
             t if t == conn.CURRENT_DESKTOP() => Self::ViewDesktop,
             t if t == conn.WM_STATE() => Self::ChangeState,
+            t if t == conn.ACTIVE_WINDOW() => Self::ActivateWindow,
+            t if t == conn.WM_DESKTOP() => Self::MoveToDesktop,
+            t if t == conn.CLOSE_WINDOW() => Self::CloseWindow,
+            t if t == conn.MOVERESIZE_WINDOW() => Self::MoveResizeWindow,
+            t if t == conn.RESTACK_WINDOW() => Self::RestackWindow,
+            t if t == util::get_atom(conn, "WM_CHANGE_STATE") => Self::ChangeWmState,
             _ => Self::NotSupported,
         }
     }
@@ -185,6 +229,12 @@ impl fmt::Display for ClientMessage {
             Self::NotSupported => write!(f, "NotSupported"),
             Self::ViewDesktop => write!(f, "ChangeDesktop"),
             Self::ChangeState => write!(f, "ChangeState"),
+            Self::ActivateWindow => write!(f, "ActivateWindow"),
+            Self::MoveToDesktop => write!(f, "MoveToDesktop"),
+            Self::CloseWindow => write!(f, "CloseWindow"),
+            Self::MoveResizeWindow => write!(f, "MoveResizeWindow"),
+            Self::RestackWindow => write!(f, "RestackWindow"),
+            Self::ChangeWmState => write!(f, "ChangeWmState"),
         }
     }
 }
@@ -1,69 +1,234 @@
 use crate::{
     event::{EventContext, ClientMessage},
+    ipc::{Ipc, IpcCommand},
     client::{
         Client,
+        ClientAction,
         ClientState,
     },
+    tag::TagKind,
     util::{self, Operation},
+    config::{Config, FocusStealingPolicy},
     errors::Error,
 };
 
 pub fn on_destroy_notify(ctx: EventContext, e: &xcb::DestroyNotifyEvent) -> Result<(), Error> {
     let mut screen = ctx.screen.lock().unwrap();
 
-    let tag = screen.get_focused_tag_mut()?;
-    let tag_id = tag.id;
+    screen.unmanage_client(e.window())?;
+    screen.refresh();
+
+    Ok(())
+}
+
+pub fn on_unmap_notify(ctx: EventContext, e: &xcb::UnmapNotifyEvent) -> Result<(), Error> {
+    let mut screen = ctx.screen.lock().unwrap();
+
+    let client = screen
+        .get_tag_of_client_mut(e.window())?
+        .get_client_mut(e.window())?;
 
-    // focus the master (first) client if any; otherwise, disable the focus.
-    if tag.get_focused_client().is_ok_and(|c| c.id == e.window()) {
-        match tag.get_first_client_when(|c| c.is_controlled()) {
-            Ok(c) => _ = tag.focus_client(c.id),
-            Err(_) => util::disable_input_focus(&ctx.conn),
-        };
+    // A synthetic event is sent by clients that withdraw while already unmapped (ICCCM 4.1.4), so
+    // it is never caused by the window manager.
+    let is_synthetic = e.response_type() & 0x80 != 0;
+    if !is_synthetic && client.consume_unmap() {
+        return Ok(())
     }
 
-    tag.unmanage_client(e.window());
+    log::info!("client withdrawn: {}", e.window());
+
+    client.withdraw(&ctx.conn);
+
+    screen.unmanage_client(e.window())?;
+    screen.refresh();
+
+    Ok(())
+}
 
-    // TODO: remove this
-    if tag.alias != "sticky_clients" {
-        _ = screen.arrange_tag(tag_id);
+pub fn on_key_release(ctx: EventContext, e: &xcb::KeyReleaseEvent) -> Result<(), Error> {
+    let mut screen = ctx.screen.lock().unwrap();
+
+    let modifier = match screen.focus_cycle_modifier() {
+        Some(m) => m,
+        None => return Ok(()),
+    };
+
+    // The event holds the modifiers from before the release, so the current ones are queried.
+    let mask = xcb::query_pointer(&ctx.conn, e.root())
+        .get_reply()
+        .map_or(0, |p| p.mask());
+
+    if mask & modifier == 0 {
+        log::trace!("focus cycle ended.");
+        screen.end_focus_cycle();
     }
+
+    Ok(())
+}
+
+pub fn on_screen_change(ctx: EventContext) -> Result<(), Error> {
+    log::info!("monitors changed, updating the screen.");
+
+    let mut screen = ctx.screen.lock().unwrap();
+    screen.update_monitors();
+
+    Ok(())
+}
+
+pub fn on_ipc_command(ctx: EventContext, ipc: &Ipc) -> Result<(), Error> {
+    let mut screen = ctx.screen.lock().unwrap();
+    let root = screen.root;
+
+    let command = match ipc.read_command(&ctx.conn, root) {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+
+    log::info!("IPC command received: {}", command);
+
+    let response = match command.parse::<IpcCommand>().and_then(|c| c.execute(&mut screen)) {
+        Ok(r) if r.is_empty() => "ok".to_owned(),
+        Ok(r) => r,
+        Err(e) => format!("error: {}", e.to_string()),
+    };
+
+    ipc.respond(&ctx.conn, root, &response);
     screen.refresh();
 
     Ok(())
 }
 
+/// Exits when another window manager acquires the manager selection, as it is replacing SapphireWM.
+pub fn on_selection_clear(ctx: EventContext, e: &xcb::SelectionClearEvent) {
+    let screen = ctx.screen.lock().unwrap();
+
+    if e.selection() != screen.selection || e.owner() != screen.check_window {
+        return
+    }
+
+    log::info!("another window manager is replacing SapphireWM, exiting.");
+
+    screen.release();
+    std::process::exit(0)
+}
+
+pub fn on_property_notify(ctx: EventContext, e: &xcb::PropertyNotifyEvent) -> Result<(), Error> {
+    let mut screen = ctx.screen.lock().unwrap();
+
+    let atom = e.atom();
+    let tag = screen.get_tag_of_client_mut(e.window())?;
+    let tag_id = tag.id;
+    let client = tag.get_client_mut(e.window())?;
+
+    // Whether the change affects the geometry of the clients.
+    let rearrange = match atom {
+        a if a == xcb::ATOM_WM_NAME || a == ctx.conn.WM_NAME() => {
+            client.update_name(&ctx.conn);
+            false
+        },
+        a if a == xcb::ATOM_WM_HINTS => {
+            // Only a change of the urgency hint itself affects the urgency, which may also have
+            // been requested through "_NET_WM_STATE_DEMANDS_ATTENTION".
+            if let Some(urgent) = client.update_hints(&ctx.conn) {
+                screen.set_client_urgency(e.window(), urgent)?;
+            }
+            false
+        },
+        a if a == xcb::ATOM_WM_NORMAL_HINTS => {
+            client.update_size_hints(&ctx.conn);
+            true
+        },
+        a if a == ctx.conn.WM_WINDOW_TYPE() => {
+            let was_dock = client.is_dock();
+            client.update_types(&ctx.conn);
+
+            // Becoming or no longer being a dock changes how the client is managed.
+            if client.is_dock() != was_dock {
+                client.classify(&ctx.conn);
+                screen.update_client_kind(e.window())?;
+                return Ok(())
+            }
+            true
+        },
+        a if a == ctx.conn.WM_STRUT_PARTIAL() || a == ctx.conn.WM_STRUT() => {
+            client.update_struts(&ctx.conn);
+            tag.update_paddings();
+            true
+        },
+        _ => false,
+    };
+
+    if rearrange {
+        log::trace!("client geometry properties changed. client={}", e.window());
+        screen.arrange_affected_by(tag_id);
+    }
+
+    Ok(())
+}
+
 pub fn on_map_request(ctx: EventContext, e: &xcb::MapRequestEvent) -> Result<(), Error> {
     log::info!("new: {}", e.window());
 
     let mut screen = ctx.screen.lock().unwrap();
 
+    // If the client has already been managed by WM, on any tag, we only need to map. Minimized
+    // clients are restored instead, and clients on hidden tags are mapped when their tag is viewed.
+    if let Ok(tag) = screen.get_tag_of_client(e.window()) {
+        if tag.get_client(e.window())?.has_state(&ClientState::Hidden) {
+            screen.restore_client(e.window())?;
+            screen.refresh();
+            return Ok(())
+        }
+
+        if screen.is_client_visible(e.window()) {
+            screen.get_tag_of_client(e.window())?.get_client(e.window())?.map(&ctx.conn);
+        }
+
+        return Ok(())
+    }
+
     // The tag represents on which tag we should manage the client.
     // Generally, the sticky tag is reserved for storing clients that must be kept on the
     // screen independently of the current tag.
-    let tag = if util::window_has_type(&ctx.conn, e.window(), ctx.conn.WM_WINDOW_TYPE_DOCK()) {
-        screen.sticky_tag_mut()
+    let tag_id = if util::window_has_type(&ctx.conn, e.window(), ctx.conn.WM_WINDOW_TYPE_DOCK()) {
+        screen.sticky_tag().id
     } else {
-        screen.get_focused_tag_mut()?
+        screen.get_focused_tag()?.id
     };
-    
-    xcb::map_window(&ctx.conn, e.window());
-    // If the client has already been managed by WM, we only need to map.
-    if tag.contains_client(e.window()) {
-        return Ok(())
-    }
 
-    let client = Client::new(&ctx.conn, e.window());
+    let mut client = Client::new(&ctx.conn, e.window());
+    client.map(&ctx.conn);
+
+    let is_scratchpad = screen.attach_scratchpad(&mut client);
+
+    let tag = screen.get_tag_mut(tag_id)?;
 
     util::set_client_tag(&ctx.conn, client.id, tag.id);
-    tag.manage_client(client);
+
+    // A client started from a terminal may take the place of the terminal.
+    let swallowed = match tag.get_first_client_when(|c| client.can_swallow(c)) {
+        Ok(c) if !is_scratchpad => Some(c.id),
+        _ => None,
+    };
+
+    match swallowed {
+        Some(id) => {
+            log::info!("client swallowed. client={} swallowed={}", client.id, id);
+            tag.swallow_client(id, client)?;
+        },
+        None => tag.manage_client(client),
+    };
     tag.focus_client_if(e.window(), |c| c.is_controlled());
 
-    // TODO: remove this
-    if tag.alias != "sticky_clients" {
-        let tag_id = tag.id;
-        _ = screen.arrange_tag(tag_id);
+    // The client may already demand attention through its `WM_HINTS`, unless it was focused.
+    let is_urgent = tag.get_client(e.window()).is_ok_and(|c| c.is_urgent());
+
+    let tag_id = tag.id;
+    if is_urgent {
+        _ = screen.set_client_urgency(e.window(), true);
     }
+
+    screen.arrange_affected_by(tag_id);
     screen.refresh();
 
     Ok(())
@@ -117,17 +282,168 @@ pub fn on_client_message(e: &xcb::ClientMessageEvent, ctx: EventContext) -> Resu
         },
         ClientMessage::ChangeState => {
             let action = Operation::from(data[0]);
-            let state = data[1];
 
-            if let Ok(t) = screen.get_focused_tag_mut() {
-                let t_id = t.id;
+            // The second and third data fields are the properties to change; the third one is zero
+            // when only one property is changed. Both are applied with the same action.
+            let states = if data[2] == data[1] { &data[1..2] } else { &data[1..3] };
+
+            for &state in states.iter().filter(|a| **a != 0) {
+                // Clients on any tag may demand attention, e.g. a chat on a hidden tag.
+                if state == ctx.conn.WM_STATE_DEMANDS_ATTENTION() {
+                    let is_urgent = screen
+                        .get_tag_of_client(e.window())?
+                        .get_client(e.window())?
+                        .is_urgent();
+
+                    let urgent = match action {
+                        Operation::Add => true,
+                        Operation::Remove => false,
+                        Operation::Toggle => !is_urgent,
+                        Operation::Unknown => return Err(Error::InvalidOperation),
+                    };
+
+                    screen.set_client_urgency(e.window(), urgent)?;
+                    continue
+                }
+
+                // Sticky clients are moved to the sticky tag, and may be on any tag.
+                if state == ctx.conn.WM_STATE_STICKY() {
+                    let tag_id = screen.get_tag_of_client(e.window())?.id;
+                    let is_sticky = tag_id == screen.sticky_tag().id;
+
+                    let sticky = match action {
+                        Operation::Add => true,
+                        Operation::Remove => false,
+                        Operation::Toggle => !is_sticky,
+                        Operation::Unknown => return Err(Error::InvalidOperation),
+                    };
 
-                if let Ok(c) = t.get_client_mut(e.window()) {
-                    if state == ctx.conn.WM_STATE_FULLSCREEN() {
-                        _ = c.set_state(&ctx.conn, ClientState::Fullscreen, action);
-                        _ = screen.arrange_tag(t_id);
+                    screen.set_client_sticky(e.window(), sticky)?;
+                    screen.refresh();
+                    continue
+                }
+
+                // Stacking states only change the stacking order, and are allowed on any tag.
+                if state == ctx.conn.WM_STATE_ABOVE() || state == ctx.conn.WM_STATE_BELOW() {
+                    let (state, opposite) = if state == ctx.conn.WM_STATE_ABOVE() {
+                        (ClientState::Above, ClientState::Below)
+                    } else {
+                        (ClientState::Below, ClientState::Above)
+                    };
+
+                    let client = screen
+                        .get_tag_of_client_mut(e.window())?
+                        .get_client_mut(e.window())?;
+
+                    client.set_state(&ctx.conn, state.clone(), action)?;
+
+                    // A client cannot be both above and below the other clients.
+                    if client.has_state(&state) {
+                        client.remove_state(&ctx.conn, opposite);
                     }
+
+                    screen.restack();
+                    continue
                 }
+
+                if let Ok(t) = screen.get_focused_tag_mut() {
+                    let t_id = t.id;
+
+                    if let Ok(c) = t.get_client_mut(e.window()) {
+                        if state == ctx.conn.WM_STATE_FULLSCREEN() {
+                            _ = c.set_state(&ctx.conn, ClientState::Fullscreen, action);
+                            _ = screen.arrange_tag(t_id);
+                        }
+                    }
+                }
+            }
+        },
+        ClientMessage::ActivateWindow => {
+            // The first data field is the source indication: 2 for pagers and taskbars, 1 for
+            // applications and 0 for clients that follow an older version of the spec, which are
+            // treated as applications.
+            let from_pager = data[0] == 2;
+
+            match Config::current().focus_stealing {
+                _ if from_pager => screen.activate_client(e.window())?,
+                FocusStealingPolicy::Always => screen.activate_client(e.window())?,
+                FocusStealingPolicy::PagerOnly => {
+                    log::info!("activation request ignored by the focus stealing policy. client={}", e.window());
+                },
+                FocusStealingPolicy::MarkUrgent => screen.set_client_urgency(e.window(), true)?,
+            };
+        },
+        ClientMessage::MoveToDesktop => {
+            let tag = screen.get_tag_of_client(e.window())?;
+            if !tag.get_client(e.window())?.allows_action(&ClientAction::ChangeTag) {
+                return Ok(())
+            }
+
+            screen.move_client(e.window(), data[0])?;
+        },
+        ClientMessage::CloseWindow => {
+            let client = screen
+                .get_tag_of_client(e.window())?
+                .get_client(e.window())?;
+
+            if client.allows_action(&ClientAction::Close) {
+                client.kill(&ctx.conn);
+            }
+        },
+        ClientMessage::MoveResizeWindow => {
+            // Bits 8 to 11 indicate which of x, y, width and height are present. The gravity is
+            // ignored, and the values are always treated as the position of the client's frame.
+            let flags = data[0] >> 8;
+
+            let tag = screen.get_tag_of_client_mut(e.window())?;
+            let (tag_id, is_sticky) = (tag.id, tag.kind == TagKind::Sticky);
+            let client = tag.get_client_mut(e.window())?;
+
+            // Only floating clients control their own geometry; the others are managed by the layout.
+            // Sticky clients always float, though their last state is `ClientState::Sticky`, while
+            // fullscreen and maximized clients fill the monitor.
+            let is_floating = matches!(client.get_state(), ClientState::Floating | ClientState::Sticky);
+            if !client.has_state(&ClientState::Floating) || !is_floating {
+                return Ok(())
+            }
+
+            // The position is signed, as clients may be partially placed outside of the root
+            // window; such positions are kept at its edges.
+            if client.allows_action(&ClientAction::Move) {
+                if flags & 0b0001 != 0 {
+                    client.geo.x = (data[1] as i32).max(0) as u32;
+                }
+                if flags & 0b0010 != 0 {
+                    client.geo.y = (data[2] as i32).max(0) as u32;
+                }
+            }
+
+            if client.allows_action(&ClientAction::Resize) {
+                if flags & 0b0100 != 0 {
+                    client.geo.w = data[3];
+                }
+                if flags & 0b1000 != 0 {
+                    client.geo.h = data[4];
+                }
+            }
+
+            (client.geo.w, client.geo.h) = client.geo.clamp_size(client.geo.w, client.geo.h);
+
+            // Sticky clients are not part of any layout, so their window is configured directly.
+            if is_sticky {
+                client.configure(&ctx.conn);
+            }
+
+            screen.arrange_affected_by(tag_id);
+        },
+        ClientMessage::RestackWindow => {
+            // The second and third data fields are the sibling and the stack mode.
+            screen.restack_client(e.window(), data[1], data[2])?;
+        },
+        ClientMessage::ChangeWmState => {
+            // Clients may only request to be iconified; see `WmState`.
+            if data[0] == 3 {
+                screen.minimize_client(e.window())?;
             }
         },
         ClientMessage::NotSupported => {
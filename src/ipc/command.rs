@@ -0,0 +1,167 @@
+use std::str::FromStr;
+
+use crate::{
+    screen::Screen,
+    monitor::MonitorID,
+    client::ClientID,
+    util::Direction,
+    errors::Error,
+};
+
+/// Represents a monitor relative to the focused one.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MonitorTarget {
+    /// The monitor after the focused one.
+    Next,
+
+    /// The monitor before the focused one.
+    Prev,
+
+    /// The closest monitor in a direction of the focused one.
+    Direction(Direction),
+
+    /// The monitor with the specified ID.
+    Id(MonitorID),
+}
+
+impl MonitorTarget {
+    /// Resolves the target to a monitor ID. Returns `None` when no monitor matches the target.
+    pub fn resolve(&self, screen: &Screen) -> Option<MonitorID> {
+        match self {
+            Self::Next => screen.get_monitor_byidx(1),
+            Self::Prev => screen.get_monitor_byidx(-1),
+            Self::Direction(d) => screen.get_monitor_in_direction(*d),
+            Self::Id(id) => screen.get_monitor(*id).ok().map(|m| m.id),
+        }
+    }
+}
+
+impl FromStr for MonitorTarget {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "next" => Ok(Self::Next),
+            "prev" => Ok(Self::Prev),
+            s if s.parse::<Direction>().is_ok() => s.parse().map(Self::Direction),
+            id => id
+                .parse::<MonitorID>()
+                .map(Self::Id)
+                .map_err(|_| Error::Custom(format!("Invalid monitor \"{}\".", id))),
+        }
+    }
+}
+
+/// Represents a command received through IPC. Commands are plain strings made of a name followed
+/// by its arguments, separated by whitespaces:
+///
+/// - `focus-monitor <next|prev|left|right|up|down|ID>`: focuses a monitor.
+/// - `move-to-monitor <next|prev|left|right|up|down|ID>`: moves the focused client to a monitor,
+///   keeping its tag index.
+/// - `swap-monitors <next|prev|left|right|up|down|ID>`: swaps the visible tags of the focused
+///   monitor and another monitor.
+/// - `view-previous-tag`: views the tag focused before the focused tag of the focused monitor.
+/// - `focus-urgent`: focuses the client that most recently demanded attention, switching to its
+///   tag.
+/// - `urgent-tags`: responds with the IDs of the tags that contain urgent clients, separated by
+///   whitespaces.
+/// - `list-minimized`: responds with the minimized clients, one per line, as
+///   `<client ID>\t<tag ID>\t<name>`.
+/// - `restore <client ID>`: restores a minimized client, activating it.
+/// - `focus-direction <left|right|up|down>`: focuses the closest client in a direction of the
+///   focused client, or the closest monitor in the direction when there is no client.
+/// - `swap-direction <left|right|up|down>`: swaps the focused client with the closest client in
+///   a direction, or moves it to the closest monitor in the direction when there is no client.
+/// - `toggle-scratchpad <name>`: shows or hides a scratchpad on the focused tag, spawning its
+///   client when absent.
+pub enum IpcCommand {
+    FocusMonitor(MonitorTarget),
+    MoveToMonitor(MonitorTarget),
+    SwapMonitors(MonitorTarget),
+    ViewPreviousTag,
+    FocusUrgent,
+    UrgentTags,
+    ListMinimized,
+    Restore(ClientID),
+    FocusDirection(Direction),
+    SwapDirection(Direction),
+    ToggleScratchpad(String),
+}
+
+impl FromStr for IpcCommand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let args: Vec<&str> = s.split_whitespace().collect();
+        let (name, args) = args.split_first().ok_or(Error::Custom("Empty command.".to_owned()))?;
+
+        let arg = |i: usize| -> Result<&str, Error> {
+            args.get(i)
+                .copied()
+                .ok_or(Error::Custom(format!("Missing argument {} of \"{}\".", i + 1, name)))
+        };
+
+        match *name {
+            "focus-monitor" => Ok(Self::FocusMonitor(arg(0)?.parse()?)),
+            "move-to-monitor" => Ok(Self::MoveToMonitor(arg(0)?.parse()?)),
+            "swap-monitors" => Ok(Self::SwapMonitors(arg(0)?.parse()?)),
+            "view-previous-tag" => Ok(Self::ViewPreviousTag),
+            "focus-urgent" => Ok(Self::FocusUrgent),
+            "urgent-tags" => Ok(Self::UrgentTags),
+            "list-minimized" => Ok(Self::ListMinimized),
+            "restore" => arg(0)?
+                .parse::<ClientID>()
+                .map(Self::Restore)
+                .map_err(|_| Error::Custom(format!("Invalid client \"{}\".", args[0]))),
+            "focus-direction" => Ok(Self::FocusDirection(arg(0)?.parse()?)),
+            "swap-direction" => Ok(Self::SwapDirection(arg(0)?.parse()?)),
+            "toggle-scratchpad" => Ok(Self::ToggleScratchpad(arg(0)?.to_owned())),
+            _ => Err(Error::Custom(format!("Unknown command \"{}\".", name))),
+        }
+    }
+}
+
+impl IpcCommand {
+    /// Executes the command on `screen`, returning the response to be sent back.
+    pub fn execute(&self, screen: &mut Screen) -> Result<String, Error> {
+        match self {
+            Self::FocusMonitor(t) => {
+                if let Some(id) = t.resolve(screen) {
+                    screen.focus_monitor(id)?;
+                }
+            },
+            Self::MoveToMonitor(t) => {
+                if let Some(id) = t.resolve(screen) {
+                    screen.move_focused_client_to_monitor(id)?;
+                }
+            },
+            Self::SwapMonitors(t) => {
+                if let Some(id) = t.resolve(screen) {
+                    let focused_id = screen.get_focused_monitor().id;
+                    screen.swap_visible_tags(focused_id, id)?;
+                }
+            },
+            Self::ViewPreviousTag => screen.view_previous_tag()?,
+            Self::FocusUrgent => screen.focus_urgent_client()?,
+            Self::UrgentTags => {
+                let tags: Vec<String> = screen.urgent_tags().iter().map(|t| t.to_string()).collect();
+                return Ok(tags.join(" "))
+            },
+            Self::ListMinimized => {
+                let clients: Vec<String> = screen
+                    .minimized_clients()
+                    .iter()
+                    .map(|(t, c)| format!("{}\t{}\t{}", c.id, t, c.wm_name.as_deref().unwrap_or("")))
+                    .collect();
+
+                return Ok(clients.join("\n"))
+            },
+            Self::Restore(id) => screen.restore_client(*id)?,
+            Self::FocusDirection(d) => screen.focus_client_in_direction(*d)?,
+            Self::SwapDirection(d) => screen.swap_client_in_direction(*d)?,
+            Self::ToggleScratchpad(name) => screen.toggle_scratchpad(name)?,
+        };
+
+        Ok(String::new())
+    }
+}
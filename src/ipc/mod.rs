@@ -0,0 +1,77 @@
+mod command;
+
+use xcb_util::ewmh;
+
+use crate::util;
+
+pub use crate::ipc::command::IpcCommand;
+
+/// Name of the root window property from which SapphireWM reads commands.
+pub const COMMAND_PROPERTY: &str = "_SAPPHIRE_COMMAND";
+
+/// Name of the root window property where SapphireWM writes the result of the last command.
+pub const RESPONSE_PROPERTY: &str = "_SAPPHIRE_RESPONSE";
+
+/// Name of the root window property where SapphireWM publishes the IDs of the tags that contain
+/// clients demanding attention.
+pub const URGENT_TAGS_PROPERTY: &str = "_SAPPHIRE_URGENT_TAGS";
+
+/// Allows external programs to control the window manager through properties of the root window.
+/// A command is sent by setting the `_SAPPHIRE_COMMAND` property to a UTF-8 string, and its result
+/// is written to the `_SAPPHIRE_RESPONSE` property. For example, with `xprop`:
+///
+/// ```sh
+/// xprop -root -f _SAPPHIRE_COMMAND 8u -set _SAPPHIRE_COMMAND "focus-monitor next"
+/// xprop -root _SAPPHIRE_RESPONSE
+/// ```
+///
+/// See `IpcCommand` for the list of supported commands.
+pub struct Ipc {
+    command: xcb::Atom,
+    response: xcb::Atom,
+    utf8_string: xcb::Atom,
+}
+
+impl Ipc {
+    pub fn new(conn: &ewmh::Connection) -> Self {
+        Self {
+            command: util::get_atom(conn, COMMAND_PROPERTY),
+            response: util::get_atom(conn, RESPONSE_PROPERTY),
+            utf8_string: util::get_atom(conn, "UTF8_STRING"),
+        }
+    }
+}
+
+impl Ipc {
+    /// Verifies if the event `e` notifies that a new command was sent to the root window `root`.
+    pub fn is_command(&self, root: u32, e: &xcb::PropertyNotifyEvent) -> bool {
+        e.window() == root && e.atom() == self.command && e.state() == xcb::PROPERTY_NEW_VALUE as u8
+    }
+
+    /// Reads and deletes the command sent to the root window `root`, if any.
+    pub fn read_command(&self, conn: &ewmh::Connection, root: u32) -> Option<String> {
+        let reply = xcb::get_property(conn, true, root, self.command, xcb::ATOM_ANY, 0, u32::MAX / 4)
+            .get_reply()
+            .ok()?;
+
+        let command = String::from_utf8_lossy(reply.value::<u8>()).trim().to_owned();
+        if command.is_empty() {
+            None
+        } else {
+            Some(command)
+        }
+    }
+
+    /// Writes the result of a command to the root window `root`.
+    pub fn respond(&self, conn: &ewmh::Connection, root: u32, response: &str) {
+        xcb::change_property(
+            conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            root,
+            self.response,
+            self.utf8_string,
+            8,
+            response.as_bytes(),
+        );
+    }
+}
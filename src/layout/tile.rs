@@ -30,7 +30,7 @@ impl Layout for LayoutTile {
 
         for (i, c) in clients.iter_mut().enumerate() {
             // TODO: padding_left
-            c.geo.x = if i == 0 { useless_gap } else { (geometry.avail_w / 2) + useless_gap };
+            c.geo.x = geometry.x + if i == 0 { useless_gap } else { (geometry.avail_w / 2) + useless_gap };
             c.geo.w = (geometry.avail_w / 2) - (useless_gap * 2) - (c.geo.border * 2);
 
             let mut height_per_window = geometry.avail_h;
@@ -38,7 +38,7 @@ impl Layout for LayoutTile {
                 height_per_window /= size - 1
             };
 
-            c.geo.y = (height_per_window * i.checked_sub(1).unwrap_or(0) as u32) + geometry.padding_top() + useless_gap;
+            c.geo.y = geometry.y + (height_per_window * i.checked_sub(1).unwrap_or(0) as u32) + geometry.padding_top() + useless_gap;
             c.geo.h = height_per_window - (c.geo.border * 2) - (useless_gap * 2);
             
             c.geo.x = c.geo.x.max(1);
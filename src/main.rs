@@ -3,16 +3,18 @@ mod client;
 mod config;
 mod errors;
 mod handlers;
+mod ipc;
 mod keyboard;
 mod layout;
+mod monitor;
 mod mouse;
 mod window_manager;
+mod scratchpad;
 mod screen;
 mod tag;
 mod util;
 mod event;
 
-
 use crate::{
     action::on_startup::OnStartup,
     client::{
@@ -22,6 +24,8 @@ use crate::{
     config::{
         Config,
         ConfigBorder,
+        ConfigSwallow,
+        FocusStealingPolicy,
     },
     event::{
         EventContext,
@@ -29,25 +33,48 @@ use crate::{
     },
     util::{
         modkeys,
+        Direction,
         Operation,
     },
     keyboard::Keybinding,
     mouse::MouseInfo,
+    scratchpad::Scratchpad,
     window_manager::WindowManager,
 };
 
 fn main() {
+    env_logger::init();
+
     Config::set(Config {
         useless_gap: 6,
         border: ConfigBorder {
             width: 2,
             color_active: 0xff9933,
             color_normal: 0x8813d2,
+            color_urgent: 0xff3333,
+        },
+        // Passing `--focus-stealing=<always|pager-only|mark-urgent>` overrides the policy.
+        focus_stealing: std::env::args()
+            .find_map(|a| a.strip_prefix("--focus-stealing=").map(|p| p.parse::<FocusStealingPolicy>()))
+            .transpose()
+            .unwrap_or_else(|e| {
+                log::error!("{}", e.to_string());
+                std::process::exit(1)
+            })
+            .unwrap_or(FocusStealingPolicy::Always),
+        scratchpads: vec![
+            Scratchpad::new("term", "alacritty --class scratchpad,Alacritty")
+                .instance("scratchpad")
+                .class("Alacritty"),
+        ],
+        swallow: ConfigSwallow {
+            swallowable: vec!["Alacritty".to_owned()],
+            never_swallow: vec!["Xephyr".to_owned()],
         },
+        mouse_follows_focus: false,
+        tag_back_and_forth: true,
     });
 
-    env_logger::init();
-
     let mut wm = WindowManager::new();
 
     wm.on_startup(&[
@@ -65,6 +92,9 @@ fn main() {
     ]);
 
     let modkey = modkeys::MODKEY_SHIFT;
+    // Shift + arrows selects text in most applications, so the directional bindings use Mod4
+    // (usually the Super key) instead of `modkey`.
+    let arrow_modkey = modkeys::MODKEY_4;
 
     wm.keyboard.append_keybindings(&[
         Keybinding::new()
@@ -88,25 +118,15 @@ fn main() {
             .execute(Box::new(|ctx: EventContext| {
                 let mut screen = ctx.screen.lock().unwrap();
 
-                let tag = screen.get_focused_tag_mut()?;
-                let tag_id = tag.id;
-
-                let client = match tag.get_focused_client() {
+                let client = match screen.get_focused_client() {
                     Ok(c) => c.clone(),
                     Err(_) => return Ok(()),
                 };
 
-                tag.unmanage_client(client.id);
+                // Also restores the client swallowed by the killed client, if any.
+                screen.unmanage_client(client.id)?;
                 client.kill(&ctx.conn);
 
-                // Focus the master (first) client if any; otherwise, disable the focus.
-                match tag.get_first_client_when(|c| c.is_controlled()) {
-                    Ok(c) => _ = tag.focus_client(c.id),
-                    Err(_) => util::disable_input_focus(&ctx.conn),
-                };
-
-                _ = screen.arrange_tag(tag_id);
-
                 Ok(())
             })),
     
@@ -126,40 +146,167 @@ fn main() {
                 screen.get_focused_tag_mut()?.focus_client_byidx(1, None)
             })),
 
+        Keybinding::new()
+            .on(&[arrow_modkey], "Left")
+            .description("Move focus to the client on the left.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.focus_client_in_direction(Direction::Left)
+            })),
+
+        Keybinding::new()
+            .on(&[arrow_modkey], "Right")
+            .description("Move focus to the client on the right.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.focus_client_in_direction(Direction::Right)
+            })),
+
+        Keybinding::new()
+            .on(&[arrow_modkey], "Up")
+            .description("Move focus to the client above.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.focus_client_in_direction(Direction::Up)
+            })),
+
+        Keybinding::new()
+            .on(&[arrow_modkey], "Down")
+            .description("Move focus to the client below.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.focus_client_in_direction(Direction::Down)
+            })),
+
+        Keybinding::new()
+            .on(&[arrow_modkey, modkeys::MODKEY_CONTROL], "Left")
+            .description("Swap the focused client with the client on the left.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.swap_client_in_direction(Direction::Left)
+            })),
+
+        Keybinding::new()
+            .on(&[arrow_modkey, modkeys::MODKEY_CONTROL], "Right")
+            .description("Swap the focused client with the client on the right.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.swap_client_in_direction(Direction::Right)
+            })),
+
+        Keybinding::new()
+            .on(&[arrow_modkey, modkeys::MODKEY_CONTROL], "Up")
+            .description("Swap the focused client with the client above.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.swap_client_in_direction(Direction::Up)
+            })),
+
+        Keybinding::new()
+            .on(&[arrow_modkey, modkeys::MODKEY_CONTROL], "Down")
+            .description("Swap the focused client with the client below.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.swap_client_in_direction(Direction::Down)
+            })),
+
         Keybinding::new()
             .on(&[modkey], "Return")
-            .description("Swaps the current client on tag to the master window.")
+            .description("Moves the focused client to the master window, or brings back the previous master.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                let tag_id = screen.focused_client_tag_id()?;
+
+                if screen.get_tag_mut(tag_id)?.zoom().is_some() {
+                    screen.arrange_affected_by(tag_id);
+                    screen.refresh()
+                }
+
+                Ok(())
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "j")
+            .description("Move the focused client down the stack.")
             .execute(Box::new(|ctx: EventContext| {
                 let mut screen = ctx.screen.lock().unwrap();
 
-                let tag = screen.get_focused_tag_mut()?;
-                let tag_id = tag.id;
+                let tag_id = screen.focused_client_tag_id()?;
+                let tag = screen.get_tag_mut(tag_id)?;
 
-                if let (Ok(c1), Ok(c2)) = (tag.get_focused_client(), tag.get_first_client_when(|c| c.is_controlled())) {
-                    _ = tag.swap(c1.id, c2.id);
-                    _ = screen.arrange_tag(tag_id);
+                if let Ok(id) = tag.get_focused_client().map(|c| c.id) {
+                    _ = tag.move_client_byidx(id, 1);
+                    screen.arrange_affected_by(tag_id);
                     screen.refresh()
                 }
 
                 Ok(())
             })),
 
+        Keybinding::new()
+            .on(&[modkey], "k")
+            .description("Move the focused client up the stack.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                let tag_id = screen.focused_client_tag_id()?;
+                let tag = screen.get_tag_mut(tag_id)?;
+
+                if let Ok(id) = tag.get_focused_client().map(|c| c.id) {
+                    _ = tag.move_client_byidx(id, -1);
+                    screen.arrange_affected_by(tag_id);
+                    screen.refresh()
+                }
+
+                Ok(())
+            })),
+
+        Keybinding::new()
+            .on(&[modkey, modkeys::MODKEY_CONTROL], "j")
+            .description("Rotate the stack forwards.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                let tag_id = screen.focused_client_tag_id()?;
+
+                screen.get_tag_mut(tag_id)?.rotate(1);
+                screen.arrange_affected_by(tag_id);
+                screen.refresh();
+
+                Ok(())
+            })),
+
+        Keybinding::new()
+            .on(&[modkey, modkeys::MODKEY_CONTROL], "k")
+            .description("Rotate the stack backwards.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                let tag_id = screen.focused_client_tag_id()?;
+
+                screen.get_tag_mut(tag_id)?.rotate(-1);
+                screen.arrange_affected_by(tag_id);
+                screen.refresh();
+
+                Ok(())
+            })),
+
         Keybinding::new()
             .on(&[modkey], "f")
             .description("Toggle fullscreen mode for the currently focused client.")
             .execute(Box::new(|ctx: EventContext| {
                 let mut screen = ctx.screen.lock().unwrap();
 
-                let tag = screen.get_focused_tag_mut()?;
-                let tag_id = tag.id;
+                let tag_id = screen.focused_client_tag_id()?;
 
-                if let Ok(c) = tag.get_focused_client_mut() {
+                if let Ok(c) = screen.get_tag_mut(tag_id)?.get_focused_client_mut() {
                     if !c.allows_action(&ClientAction::Fullscreen) {
                         return Ok(())
                     }
 
                     c.set_state(&ctx.conn, ClientState::Fullscreen, Operation::Toggle)?;
-                    _ = screen.arrange_tag(tag_id);
+                    screen.arrange_affected_by(tag_id);
                 }
 
                 Ok(())
@@ -171,37 +318,180 @@ fn main() {
             .execute(Box::new(|ctx: EventContext| {
                 let mut screen = ctx.screen.lock().unwrap();
 
-                let tag = screen.get_focused_tag_mut()?;
-                let tag_id = tag.id;
+                let tag_id = screen.focused_client_tag_id()?;
 
-                if let Ok(c) = tag.get_focused_client_mut() {
+                if let Ok(c) = screen.get_tag_mut(tag_id)?.get_focused_client_mut() {
                     if !c.allows_action(&ClientAction::Maximize) {
                         return Ok(())
                     }
 
                     c.set_state(&ctx.conn, ClientState::Maximized, Operation::Toggle)?;
-                    _ = screen.arrange_tag(tag_id);
+                    screen.arrange_affected_by(tag_id);
+                }
+
+                Ok(())
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "space")
+            .description("Toggle floating mode for the currently focused client.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                let tag_id = screen.focused_client_tag_id()?;
+
+                if let Ok(c) = screen.get_tag_mut(tag_id)?.get_focused_client_mut() {
+                    if !c.allows_action(&ClientAction::Move) {
+                        return Ok(())
+                    }
+
+                    c.set_state(&ctx.conn, ClientState::Floating, Operation::Toggle)?;
+                    screen.arrange_affected_by(tag_id);
+                }
+
+                Ok(())
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "grave")
+            .description("Toggle the terminal scratchpad.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.toggle_scratchpad("term")
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "p")
+            .description("Toggle sticky mode for the focused client, showing it on every tag.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                // The focused client may be either on the focused tag or sticky.
+                let id = match screen.get_focused_client() {
+                    Ok(c) => c.id,
+                    Err(_) => return Ok(()),
+                };
+
+                let is_sticky = screen.get_tag_of_client(id)?.id == screen.sticky_tag().id;
+
+                screen.set_client_sticky(id, !is_sticky)?;
+                screen.refresh();
+
+                Ok(())
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "n")
+            .description("Minimize the focused client.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                let id = match screen.get_focused_client() {
+                    Ok(c) => c.id,
+                    Err(_) => return Ok(()),
+                };
+
+                screen.minimize_client(id)
+            })),
+
+        Keybinding::new()
+            .on(&[modkey, modkeys::MODKEY_CONTROL], "n")
+            .description("Restore the most recent minimized client of the focused tag.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                let id = match screen.get_focused_tag()?.minimized_clients().next() {
+                    Some(c) => c.id,
+                    None => return Ok(()),
+                };
+
+                screen.restore_client(id)
+            })),
+
+        Keybinding::new()
+            .on(&[modkeys::MODKEY_1], "Tab")
+            .description("Cycle through the most recently focused clients while Alt is held.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.cycle_focus(modkeys::MODKEY_1, 1)
+            })),
+
+        Keybinding::new()
+            .on(&[modkeys::MODKEY_1, modkeys::MODKEY_SHIFT], "Tab")
+            .description("Cycle backwards through the most recently focused clients while Alt is held.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.cycle_focus(modkeys::MODKEY_1, -1)
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "u")
+            .description("Focus the most recent urgent client, switching to its tag.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.focus_urgent_client()
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "period")
+            .description("Focus the next monitor.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.focus_monitor_byidx(1)
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "comma")
+            .description("Focus the previous monitor.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.focus_monitor_byidx(-1)
+            })),
+
+        Keybinding::new()
+            .on(&[modkey, modkeys::MODKEY_CONTROL], "period")
+            .description("Move the focused client to the next monitor.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                if let Some(id) = screen.get_monitor_byidx(1) {
+                    screen.move_focused_client_to_monitor(id)?;
+                }
+
+                Ok(())
+            })),
+
+        Keybinding::new()
+            .on(&[modkey, modkeys::MODKEY_CONTROL], "comma")
+            .description("Move the focused client to the previous monitor.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+
+                if let Some(id) = screen.get_monitor_byidx(-1) {
+                    screen.move_focused_client_to_monitor(id)?;
                 }
 
                 Ok(())
             })),
     ]);
 
-    // Bind MODKEY + i to desktop[i].
-    for id in 0..9 as u32 {
-        if id > 8 {
+    // Bind MODKEY + i to desktop[i] of the focused monitor.
+    for idx in 0..9usize {
+        if idx > 8 {
             break
         }
 
-        let key = (id+1).to_string();
+        let key = (idx+1).to_string();
 
         wm.keyboard.append_keybindings(&[
             Keybinding::new()
                 .on(&[modkey], key.as_str())
-                .description("View tag[i].")
+                .description("View tag[i], or the previous tag when tag[i] is already focused.")
                 .execute(Box::new(move |ctx: EventContext| {
                     let mut screen = ctx.screen.lock().unwrap();
-                    screen.view_tag(id)
+
+                    let id = screen.get_focused_monitor().get_tag_id(idx)?;
+                    screen.view_tag_back_and_forth(id)
                 })),
 
             Keybinding::new()
@@ -210,7 +500,8 @@ fn main() {
                 .execute(Box::new(move |ctx: EventContext| {
                     let mut screen = ctx.screen.lock().unwrap();
 
-                    let curr_tag_id = screen.get_focused_tag().map(|t| t.id)?;
+                    let id = screen.get_focused_monitor().get_tag_id(idx)?;
+                    let curr_tag_id = screen.focused_client_tag_id()?;
                     if id != curr_tag_id {
                         _ = screen.move_focused_client(curr_tag_id, id)?;
                         // Optionally, follow
@@ -219,19 +510,74 @@ fn main() {
 
                     Ok(())
                 })),
+
+            Keybinding::new()
+                .on(&[modkey, modkeys::MODKEY_1], key.as_str())
+                .description("Toggle viewing tag[i] together with the viewed tags.")
+                .execute(Box::new(move |ctx: EventContext| {
+                    let mut screen = ctx.screen.lock().unwrap();
+
+                    let id = screen.get_focused_monitor().get_tag_id(idx)?;
+                    screen.toggle_view_tag(id)
+                })),
+
+            Keybinding::new()
+                .on(&[modkey, modkeys::MODKEY_1, modkeys::MODKEY_CONTROL], key.as_str())
+                .description("Toggle showing the focused client on tag[i].")
+                .execute(Box::new(move |ctx: EventContext| {
+                    let mut screen = ctx.screen.lock().unwrap();
+
+                    let id = screen.get_focused_monitor().get_tag_id(idx)?;
+                    let client_id = match screen.get_focused_client() {
+                        Ok(c) => c.id,
+                        Err(_) => return Ok(()),
+                    };
+
+                    screen.toggle_client_tag(client_id, id)
+                })),
         ]);
     }
 
+    wm.keyboard.append_keybindings(&[
+        Keybinding::new()
+            .on(&[modkey], "Escape")
+            .description("View the previously focused tag.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.view_previous_tag()
+            })),
+
+        Keybinding::new()
+            .on(&[modkey], "0")
+            .description("View all tags of the focused monitor.")
+            .execute(Box::new(|ctx: EventContext| {
+                let mut screen = ctx.screen.lock().unwrap();
+                screen.view_all_tags()
+            })),
+    ]);
+
     // Enables focus on click.
     wm.mouse.on(MouseEvent::Click, Box::new(|ctx: EventContext, info: MouseInfo| {
         let mut screen = ctx.screen.lock().unwrap();
 
-        let tag = screen.get_focused_tag_mut()?;
-        if info.c_id != tag.get_focused_client()?.id {
-            tag.focus_client_if(info.c_id, |c| c.is_controlled());
+        // Clicking on another monitor also moves the focus to it.
+        if let Some(m) = screen.get_monitor_at(info.x.max(0) as u32, info.y.max(0) as u32).map(|m| m.id) {
+            screen.focus_monitor(m)?;
+        }
+
+        // The client may be on the focused tag, on another viewed tag or be sticky.
+        screen.focus_client(info.c_id)
+    }));
+
+    // Enables sloppy focus: the client under the pointer is focused.
+    wm.mouse.on(MouseEvent::Enter, Box::new(|ctx: EventContext, info: MouseInfo| {
+        let mut screen = ctx.screen.lock().unwrap();
+
+        if let Some(m) = screen.get_monitor_at(info.x.max(0) as u32, info.y.max(0) as u32).map(|m| m.id) {
+            screen.focus_monitor(m)?;
         }
 
-        Ok(())
+        screen.focus_client(info.c_id)
     }));
 
     wm.run();
@@ -0,0 +1,90 @@
+use crate::{
+    client::ClientGeometry,
+    util::Direction,
+};
+
+/// Represents the area of the root window covered by a monitor. Coordinates are relative to the
+/// top-left corner of the root window.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MonitorGeometry {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl MonitorGeometry {
+    /// Creates a new `MonitorGeometry` instance with the given position and dimensions.
+    pub fn new(x: u32, y: u32, w: u32, h: u32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Verifies if the point `(x, y)` is inside the monitor.
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x < self.x + self.w && y >= self.y && y < self.y + self.h
+    }
+
+    /// Returns the center of the monitor as a `(x, y)` tuple.
+    pub fn center(&self) -> (i64, i64) {
+        ((self.x + self.w / 2) as i64, (self.y + self.h / 2) as i64)
+    }
+
+    /// Verifies if `other` is entirely placed in the direction `dir` of the monitor.
+    pub fn is_in_direction(&self, dir: Direction, other: &MonitorGeometry) -> bool {
+        match dir {
+            Direction::Left => other.x + other.w <= self.x,
+            Direction::Right => other.x >= self.x + self.w,
+            Direction::Up => other.y + other.h <= self.y,
+            Direction::Down => other.y >= self.y + self.h,
+        }
+    }
+
+    /// Moves a client placed on the monitor `from` to the same position relative to this monitor,
+    /// keeping it inside the area left by `paddings`, which follow the same order as
+    /// `TagGeometry::paddings`.
+    pub fn translate_client(&self, from: &MonitorGeometry, paddings: [u32; 4], geo: &mut ClientGeometry) {
+        let [top, bottom, left, right] = paddings;
+
+        let x = self.x + geo.x.saturating_sub(from.x);
+        let y = self.y + geo.y.saturating_sub(from.y);
+
+        let max_x = (self.x + self.w).saturating_sub(right + geo.w + geo.border * 2);
+        let max_y = (self.y + self.h).saturating_sub(bottom + geo.h + geo.border * 2);
+
+        geo.x = x.min(max_x).max(self.x + left);
+        geo.y = y.min(max_y).max(self.y + top);
+    }
+
+    /// Converts the struts of a client, which are relative to the edges of the root window with
+    /// size `root_w`x`root_h`, into paddings relative to the edges of the monitor. A strut only
+    /// reserves space in the monitor when its range overlaps the monitor.
+    ///
+    /// The returned array follows the same order as `TagGeometry::paddings`.
+    pub fn paddings_from_struts(&self, root_w: u32, root_h: u32, geo: &ClientGeometry) -> [u32; 4] {
+        // A strut without range (e.g. set through `_NET_WM_STRUT`) spans the entire edge.
+        let overlaps = |(start, end): (u32, u32), from: u32, len: u32| {
+            (start == 0 && end == 0) || (start < from + len && end >= from)
+        };
+
+        let [top, bottom, left, right] = geo.paddings;
+        let mut paddings = [0, 0, 0, 0];
+
+        if top > 0 && overlaps(geo.strut_ranges[0], self.x, self.w) {
+            paddings[0] = top.saturating_sub(self.y).min(self.h);
+        }
+
+        if bottom > 0 && overlaps(geo.strut_ranges[1], self.x, self.w) {
+            paddings[1] = (self.y + self.h).saturating_sub(root_h.saturating_sub(bottom)).min(self.h);
+        }
+
+        if left > 0 && overlaps(geo.strut_ranges[2], self.y, self.h) {
+            paddings[2] = left.saturating_sub(self.x).min(self.w);
+        }
+
+        if right > 0 && overlaps(geo.strut_ranges[3], self.y, self.h) {
+            paddings[3] = (self.x + self.w).saturating_sub(root_w.saturating_sub(right)).min(self.w);
+        }
+
+        paddings
+    }
+}
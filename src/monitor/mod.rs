@@ -0,0 +1,392 @@
+mod geometry;
+mod randr;
+mod xinerama;
+
+use std::sync::Arc;
+
+use xcb_util::ewmh;
+
+use crate::{
+    client::{Client, ClientID, ClientState},
+    layout::Layout,
+    tag::{
+        Tag,
+        TagID,
+        TagKind,
+        TagGeometry,
+    },
+    errors::Error,
+    util::Direction,
+};
+
+pub use crate::monitor::geometry::MonitorGeometry;
+
+/// Represents the ID of a monitor. It is the position of the monitor in the list of monitors,
+/// which is sorted from left to right and top to bottom.
+pub type MonitorID = usize;
+
+/// Represents an output detected by one of the monitor-detection backends.
+pub struct MonitorInfo {
+    /// The name of the output, e.g. "eDP-1" or "HDMI-1".
+    pub name: String,
+
+    pub geo: MonitorGeometry,
+}
+
+/// Detects the monitors of the screen with root window `root`, sorted from left to right and top
+/// to bottom.
+///
+/// RandR is the preferred backend. Xinerama is used when RandR is unavailable or when it reports a
+/// single monitor while Xinerama exposes more heads (e.g. VNC servers or Xephyr with `+xinerama`).
+/// When no backend is able to detect the monitors, a single monitor covering the entire root window
+/// with size `width`x`height` is returned. It never returns an empty vector.
+pub fn detect(conn: &ewmh::Connection, root: u32, width: u32, height: u32) -> Vec<MonitorInfo> {
+    let monitors = match randr::query_monitors(conn, root) {
+        Some(m) if m.len() > 1 => Some(m),
+        randr => match xinerama::query_monitors(conn) {
+            Some(x) if randr.as_ref().is_none_or(|r| x.len() > r.len()) => Some(x),
+            _ => randr,
+        },
+    };
+
+    let mut monitors = monitors.unwrap_or_else(|| {
+        vec![MonitorInfo {
+            name: String::from("default"),
+            geo: MonitorGeometry::new(0, 0, width, height),
+        }]
+    });
+
+    monitors.sort_by_key(|m| (m.geo.x, m.geo.y));
+    monitors
+}
+
+/// Subscribes to the notifications sent when monitors are added, removed or resized. Returns the
+/// first event code of the RandR extension, or `None` when monitor changes cannot be tracked.
+pub fn listen_changes(conn: &ewmh::Connection, root: u32) -> Option<u8> {
+    randr::select_changes(conn, root)
+}
+
+/// Verifies if an event with response type `response_type` is a RandR notification, given the
+/// first event code `base` of the extension.
+pub fn is_change_event(base: u8, response_type: u8) -> bool {
+    let r#type = response_type & !0x80;
+    r#type == base + xcb::randr::SCREEN_CHANGE_NOTIFY || r#type == base + xcb::randr::NOTIFY
+}
+
+/// Represents a physical output, such as a laptop panel or an external display. Each monitor
+/// manages its own set of tags and keeps track of which one is currently visible.
+pub struct Monitor {
+    pub id: MonitorID,
+
+    /// The name of the output, e.g. "eDP-1" or "HDMI-1".
+    pub name: String,
+
+    pub geo: MonitorGeometry,
+
+    /// ID of the currently focused (visible) tag of the monitor.
+    focused_tag_id: TagID,
+
+    /// Bitmask of the tags viewed by the monitor, dwm style: bit `i` is the tag at position `i`.
+    /// The focused tag is always viewed, but other tags may be viewed together with it; see
+    /// `Screen::toggle_view_tag`.
+    view_mask: u32,
+
+    /// IDs of the previously focused tags of the monitor, from the oldest to the most recent. The
+    /// focused tag is never in the history and each tag appears at most once.
+    tag_history: Vec<TagID>,
+
+    /// The tags of the monitor. This vector is never empty.
+    tags: Vec<Tag>,
+}
+
+impl Monitor {
+    /// Creates a new monitor with one tag per alias in `aliases`. The ID of the tags starts at
+    /// `first_tag_id` and is incremented by one for each alias. The first tag is the focused one.
+    pub fn new(
+        conn: Arc<ewmh::Connection>,
+        id: MonitorID,
+        info: MonitorInfo,
+        aliases: &[String],
+        first_tag_id: TagID,
+    ) -> Self {
+        log::trace!(
+            "creating monitor. id={} name={} geo={{x={} y={} w={} h={}}}",
+            id,
+            info.name,
+            info.geo.x,
+            info.geo.y,
+            info.geo.w,
+            info.geo.h,
+        );
+
+        let tags = aliases
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                Tag::new(
+                    conn.clone(),
+                    first_tag_id + i as u32,
+                    a,
+                    TagKind::Normal,
+                    TagGeometry::new(info.geo.x, info.geo.y, info.geo.w, info.geo.h, [0, 0, 0, 0]),
+                )
+            })
+            .collect();
+
+        Self {
+            id,
+            name: info.name,
+            geo: info.geo,
+            focused_tag_id: first_tag_id,
+            view_mask: 1,
+            tag_history: Vec::new(),
+            tags,
+        }
+    }
+}
+
+impl Monitor {
+    /// Returns the ID of the focused tag of the monitor.
+    #[inline(always)]
+    pub fn focused_tag_id(&self) -> TagID {
+        self.focused_tag_id
+    }
+
+    /// Sets the focused tag of the monitor. Returns `Error::TagNotFound(id)` when the monitor does
+    /// not contain the tag.
+    pub fn set_focused_tag_id(&mut self, id: TagID) -> Result<(), Error> {
+        if !self.contains_tag(id) {
+            return Err(Error::TagNotFound(id))
+        }
+
+        // Focusing a tag that is not viewed replaces the view.
+        if !self.is_tag_viewed(id) {
+            self.view_mask = self.tag_bit(id);
+        }
+
+        self.update_tag_history(id);
+        self.focused_tag_id = id;
+        Ok(())
+    }
+
+    /// Returns the ID of the tag focused before the focused tag, if any.
+    pub fn previous_tag_id(&self) -> Option<TagID> {
+        self.tag_history.last().copied()
+    }
+
+    /// Records the focused tag in the history when the tag with ID `id` becomes the focused one.
+    fn update_tag_history(&mut self, id: TagID) {
+        if id == self.focused_tag_id {
+            return
+        }
+
+        let focused = self.focused_tag_id;
+        self.tag_history.retain(|t| *t != id && *t != focused);
+        self.tag_history.push(focused);
+    }
+
+    /// Returns the bitmask of the tags viewed by the monitor.
+    #[inline(always)]
+    pub fn view_mask(&self) -> u32 {
+        self.view_mask
+    }
+
+    /// Views the tags in `mask`, with the tag with ID `focused` as the focused tag, which is viewed
+    /// even if it is not in `mask`. Returns `Error::TagNotFound(focused)` when the monitor does not
+    /// contain the tag.
+    /// Note: It does not map or unmap any client; see `Screen::set_view`.
+    pub fn set_view(&mut self, focused: TagID, mask: u32) -> Result<(), Error> {
+        if !self.contains_tag(focused) {
+            return Err(Error::TagNotFound(focused))
+        }
+
+        self.view_mask = mask | self.tag_bit(focused);
+        self.update_tag_history(focused);
+        self.focused_tag_id = focused;
+
+        Ok(())
+    }
+
+    /// Returns the bit of the tag with ID `id` in the bitmasks of the monitor, or 0 when the
+    /// monitor does not contain the tag.
+    pub fn tag_bit(&self, id: TagID) -> u32 {
+        self.tags
+            .iter()
+            .position(|t| t.id == id)
+            .and_then(|i| 1u32.checked_shl(i as u32))
+            .unwrap_or(0)
+    }
+
+    /// Returns the bitmask with all tags of the monitor.
+    pub fn all_tags_mask(&self) -> u32 {
+        self.tags.iter().fold(0, |mask, t| mask | self.tag_bit(t.id))
+    }
+
+    /// Verifies if the tag with ID `id` is viewed by the monitor.
+    pub fn is_tag_viewed(&self, id: TagID) -> bool {
+        self.view_mask & self.tag_bit(id) != 0
+    }
+
+    /// Verifies if the client `c`, managed by the tag with ID `tag_id`, is shown by the view of
+    /// the monitor, either through its tag or through its `Client::tag_mask`.
+    fn shows(&self, tag_id: TagID, c: &Client) -> bool {
+        self.is_tag_viewed(tag_id) || c.tag_mask & self.view_mask != 0
+    }
+
+    /// Verifies if the client with ID `id` is shown by the view of the monitor. Minimized clients
+    /// are never shown.
+    pub fn is_client_visible(&self, id: ClientID) -> bool {
+        self.tags
+            .iter()
+            .find_map(|t| t.get_client(id).ok().map(|c| (t.id, c)))
+            .is_some_and(|(t, c)| self.shows(t, c) && !c.has_state(&ClientState::Hidden))
+    }
+
+    /// Returns the IDs of the clients shown by the view of the monitor. Minimized clients are
+    /// never shown.
+    pub fn visible_clients(&self) -> Vec<ClientID> {
+        self.tags
+            .iter()
+            .flat_map(|t| t.clients().map(move |c| (t.id, c)))
+            .filter(|(t, c)| self.shows(*t, c) && !c.has_state(&ClientState::Hidden))
+            .map(|(_, c)| c.id)
+            .collect()
+    }
+
+    /// Retrieves the closest client shown by the view of the monitor in the direction `dir` of the
+    /// client `relative`, based on their geometries. Clients of every viewed tag are considered.
+    /// Returns `None` when there is no client in the direction.
+    pub fn get_client_in_direction(&self, dir: Direction, relative: &Client) -> Option<&Client> {
+        let (x, y) = relative.geo.center();
+
+        self.tags
+            .iter()
+            .flat_map(|t| t.clients().map(move |c| (t.id, c)))
+            .filter(|(t, c)| self.shows(*t, c) && c.id != relative.id && c.is_focusable())
+            .map(|(_, c)| c)
+            .filter(|c| relative.geo.is_in_direction(dir, &c.geo))
+            .min_by_key(|c| {
+                let (cx, cy) = c.geo.center();
+                (cx - x).abs() + (cy - y).abs()
+            })
+    }
+
+    /// Readjusts the layout of the clients shown by the view of the monitor, which may come from
+    /// several tags. They are arranged together in the geometry of the focused tag, after its own
+    /// clients. `paddings` are the paddings reserved by the sticky clients on the monitor.
+    pub fn arrange<T>(&mut self, layout: &T, paddings: [u32; 4])
+    where
+        T: Layout
+    {
+        let view_mask = self.view_mask;
+
+        // The paddings of every viewed tag are reserved.
+        let mut paddings = paddings;
+        for t in self.tags.iter().filter(|t| self.is_tag_viewed(t.id)) {
+            for (p, tp) in paddings.iter_mut().zip(t.geo.paddings) {
+                *p = (*p).max(tp);
+            }
+        }
+
+        let mut focused = None;
+        let mut others: Vec<&mut Client> = Vec::new();
+
+        for (i, t) in self.tags.iter_mut().enumerate() {
+            if t.id == self.focused_tag_id {
+                focused = Some(t);
+                continue
+            }
+
+            let is_viewed = view_mask & 1u32.checked_shl(i as u32).unwrap_or(0) != 0;
+            others.extend(t.clients_mut().filter(|c| is_viewed || c.tag_mask & view_mask != 0));
+        }
+
+        if let Some(t) = focused {
+            t.arrange_with(layout, paddings, others);
+        }
+    }
+
+    /// Updates the geometry of the monitor and of all its tags. The paddings of the tags are kept.
+    pub fn set_geometry(&mut self, geo: MonitorGeometry) {
+        self.tags
+            .iter_mut()
+            .for_each(|t| t.geo.resize(geo.x, geo.y, geo.w, geo.h));
+
+        self.geo = geo;
+    }
+
+    /// Reassigns the IDs of the tags of the monitor sequentially, starting at `first_tag_id`. The
+    /// focused tag and the tag history remain the same.
+    pub fn renumber_tags(&mut self, first_tag_id: TagID) {
+        let focused_idx = self.tags
+            .iter()
+            .position(|t| t.id == self.focused_tag_id)
+            .unwrap_or(0);
+
+        let tags = &self.tags;
+        self.tag_history = self.tag_history
+            .iter()
+            .filter_map(|id| tags.iter().position(|t| t.id == *id))
+            .map(|idx| first_tag_id + idx as u32)
+            .collect();
+
+        self.tags
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, t)| t.id = first_tag_id + i as u32);
+
+        self.focused_tag_id = first_tag_id + focused_idx as u32;
+    }
+
+    /// Verifies if the monitor contains a tag with ID `id`.
+    pub fn contains_tag(&self, id: TagID) -> bool {
+        self.tags.iter().any(|t| t.id == id)
+    }
+
+    /// Returns the ID of the tag at position `idx` of the monitor. Returns `Error::TagNotFound(idx)`
+    /// when the monitor has less than `idx + 1` tags.
+    pub fn get_tag_id(&self, idx: usize) -> Result<TagID, Error> {
+        self.tags
+            .get(idx)
+            .map(|t| t.id)
+            .ok_or(Error::TagNotFound(idx as u32))
+    }
+
+    /// Returns an iterator over the tags of the monitor.
+    pub fn tags(&self) -> impl Iterator<Item = &Tag> {
+        self.tags.iter()
+    }
+
+    /// Returns a mutable iterator over the tags of the monitor.
+    pub fn tags_mut(&mut self) -> impl Iterator<Item = &mut Tag> {
+        self.tags.iter_mut()
+    }
+
+    /// Returns a immutable reference to the specified tag or `Error::TagNotFound(id)` when the
+    /// monitor does not contain it.
+    pub fn get_tag(&self, id: TagID) -> Result<&Tag, Error> {
+        self.tags
+            .iter()
+            .find(|t| t.id == id)
+            .ok_or(Error::TagNotFound(id))
+    }
+
+    /// Returns a mutable reference to the specified tag or `Error::TagNotFound(id)` when the
+    /// monitor does not contain it.
+    pub fn get_tag_mut(&mut self, id: TagID) -> Result<&mut Tag, Error> {
+        self.tags
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or(Error::TagNotFound(id))
+    }
+
+    /// Returns a immutable reference to the focused tag of the monitor.
+    pub fn get_focused_tag(&self) -> Result<&Tag, Error> {
+        self.get_tag(self.focused_tag_id)
+    }
+
+    /// Returns a mutable reference to the focused tag of the monitor.
+    pub fn get_focused_tag_mut(&mut self) -> Result<&mut Tag, Error> {
+        self.get_tag_mut(self.focused_tag_id)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::monitor::{
+    MonitorGeometry,
+    MonitorInfo,
+};
+
+/// Queries the RandR extension for the active outputs of the screen with root window `root`. Each
+/// connected output driven by a CRTC becomes a monitor; outputs that mirror the same CRTC (clones)
+/// are merged into a single one.
+///
+/// Returns `None` when the extension is unavailable or when no output is active.
+pub fn query_monitors(conn: &xcb::Connection, root: u32) -> Option<Vec<MonitorInfo>> {
+    if !conn.get_extension_data(xcb::randr::id()).is_some_and(|e| e.present()) {
+        log::warn!("RandR extension is not available.");
+        return None
+    }
+
+    let resources = xcb::randr::get_screen_resources_current(conn, root)
+        .get_reply()
+        .ok()?;
+
+    let mut monitors: Vec<MonitorInfo> = Vec::new();
+
+    for &output in resources.outputs() {
+        let output = match xcb::randr::get_output_info(conn, output, resources.config_timestamp()).get_reply() {
+            Ok(o) => o,
+            Err(_) => continue,
+        };
+
+        if output.connection() != xcb::randr::CONNECTION_CONNECTED as u8 || output.crtc() == xcb::NONE {
+            continue
+        }
+
+        let crtc = match xcb::randr::get_crtc_info(conn, output.crtc(), resources.config_timestamp()).get_reply() {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+
+        if crtc.width() == 0 || crtc.height() == 0 {
+            continue
+        }
+
+        let geo = MonitorGeometry::new(
+            crtc.x().max(0) as u32,
+            crtc.y().max(0) as u32,
+            crtc.width() as u32,
+            crtc.height() as u32,
+        );
+
+        if monitors.iter().any(|m| m.geo == geo) {
+            continue
+        }
+
+        monitors.push(MonitorInfo {
+            name: String::from_utf8_lossy(output.name()).into_owned(),
+            geo,
+        });
+    }
+
+    if monitors.is_empty() {
+        None
+    } else {
+        Some(monitors)
+    }
+}
+
+/// Subscribes to the RandR notifications sent when the screen is resized or when outputs and CRTCs
+/// change, e.g. when a monitor is plugged or its resolution changes.
+///
+/// Returns the first event code of the extension, used to identify its events, or `None` when
+/// the extension is unavailable.
+pub fn select_changes(conn: &xcb::Connection, root: u32) -> Option<u8> {
+    let ext = conn.get_extension_data(xcb::randr::id()).filter(|e| e.present())?;
+
+    xcb::randr::select_input(
+        conn,
+        root,
+        (xcb::randr::NOTIFY_MASK_SCREEN_CHANGE | xcb::randr::NOTIFY_MASK_CRTC_CHANGE | xcb::randr::NOTIFY_MASK_OUTPUT_CHANGE) as u16,
+    );
+
+    Some(ext.first_event())
+}
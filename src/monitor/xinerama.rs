@@ -0,0 +1,52 @@
+use crate::monitor::{
+    MonitorGeometry,
+    MonitorInfo,
+};
+
+/// Queries the Xinerama extension for the heads of the screen. It is used as a fallback for X
+/// servers where RandR is unavailable or reports a single screen spanning all heads, such as VNC
+/// servers or nested servers started with `+xinerama`. Heads with the same geometry are merged.
+///
+/// Returns `None` when the extension is unavailable, inactive or reports no head.
+pub fn query_monitors(conn: &xcb::Connection) -> Option<Vec<MonitorInfo>> {
+    if !conn.get_extension_data(xcb::xinerama::id()).is_some_and(|e| e.present()) {
+        log::warn!("Xinerama extension is not available.");
+        return None
+    }
+
+    if !xcb::xinerama::is_active(conn).get_reply().is_ok_and(|r| r.state() != 0) {
+        return None
+    }
+
+    let reply = xcb::xinerama::query_screens(conn).get_reply().ok()?;
+
+    let mut monitors: Vec<MonitorInfo> = Vec::new();
+
+    for (i, head) in reply.screen_info().enumerate() {
+        if head.width() == 0 || head.height() == 0 {
+            continue
+        }
+
+        let geo = MonitorGeometry::new(
+            head.x_org().max(0) as u32,
+            head.y_org().max(0) as u32,
+            head.width() as u32,
+            head.height() as u32,
+        );
+
+        if monitors.iter().any(|m| m.geo == geo) {
+            continue
+        }
+
+        monitors.push(MonitorInfo {
+            name: format!("xinerama-{}", i),
+            geo,
+        });
+    }
+
+    if monitors.is_empty() {
+        None
+    } else {
+        Some(monitors)
+    }
+}
@@ -22,6 +22,7 @@ pub struct Mouse {
     conn: Arc<ewmh::Connection>,
     events: Vec<MouseEvent>,
     on_click: Vec<Box<dyn FnOnClick>>,
+    on_enter: Vec<Box<dyn FnOnClick>>,
 }
 
 impl Mouse {
@@ -39,6 +40,7 @@ impl Mouse {
             conn,
             events: vec![],
             on_click: vec![],
+            on_enter: vec![],
         }
     }
 }
@@ -69,7 +71,9 @@ impl Mouse {
                     1,
                     xcb::MOD_MASK_ANY as u16,
                 );
-            }
+            },
+            // The enter window mask is selected on every client; see `Client::new`.
+            MouseEvent::Enter => (),
         };
     
         self.events.push(e);
@@ -90,7 +94,10 @@ impl Mouse {
             self.listen_event(e);
         }
 
-        self.on_click.push(dyn_clone::clone_box(&*cb));
+        match e {
+            MouseEvent::Click => self.on_click.push(dyn_clone::clone_box(&*cb)),
+            MouseEvent::Enter => self.on_enter.push(dyn_clone::clone_box(&*cb)),
+        };
     }
 
     /// Triggers the event `e` with the provided context and information.
@@ -101,6 +108,11 @@ impl Mouse {
                     cb.call(ctx.clone(), info.clone())?;
                 }
             },
+            MouseEvent::Enter => {
+                for cb in self.on_enter.iter() {
+                    cb.call(ctx.clone(), info.clone())?;
+                }
+            },
         }
 
         Ok(())
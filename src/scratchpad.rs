@@ -0,0 +1,60 @@
+use crate::client::Client;
+
+/// Represents a named scratchpad: a floating client that is toggled on the focused tag with a
+/// single action and hidden from all layouts otherwise. The client is spawned with `command` on
+/// the first toggle and is recognized by its `WM_CLASS` when mapped.
+///
+/// ```
+/// Scratchpad::new("term", "alacritty --class scratchpad").instance("scratchpad")
+/// ```
+#[derive(Clone)]
+pub struct Scratchpad {
+    /// Name used to toggle the scratchpad.
+    pub name: String,
+
+    /// Command spawned when the scratchpad has no client.
+    pub command: String,
+
+    /// The class (second part of `WM_CLASS`) that the client must have, if any.
+    pub class: Option<String>,
+
+    /// The instance (first part of `WM_CLASS`) that the client must have, if any.
+    pub instance: Option<String>,
+}
+
+impl Scratchpad {
+    pub fn new(name: &str, command: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            command: command.to_owned(),
+            class: None,
+            instance: None,
+        }
+    }
+
+    /// Sets the class that the client must have.
+    pub fn class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_owned());
+        self
+    }
+
+    /// Sets the instance that the client must have.
+    pub fn instance(mut self, instance: &str) -> Self {
+        self.instance = Some(instance.to_owned());
+        self
+    }
+
+    /// Verifies if `client` belongs to the scratchpad. A scratchpad without any rule never
+    /// matches.
+    pub fn matches(&self, client: &Client) -> bool {
+        if self.class.is_none() && self.instance.is_none() {
+            return false
+        }
+
+        let matches = |rule: &Option<String>, value: &Option<String>| {
+            rule.as_ref().is_none_or(|r| value.as_ref() == Some(r))
+        };
+
+        matches(&self.class, &client.wm_class) && matches(&self.instance, &client.wm_instance)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::client::{Client, ClientID};
+
+/// Represents an alt-tab style cycle through the focus history, started by `Screen::cycle_focus`.
+/// The keyboard is grabbed while cycling, and the cycle ends when its modifier is released; see
+/// `Screen::end_focus_cycle`.
+pub struct FocusCycle {
+    /// The modifier that must be held to keep cycling.
+    pub modifier: u16,
+
+    /// The clients being cycled, from the most recently focused.
+    pub clients: Vec<ClientID>,
+
+    /// Index in `clients` of the selected client.
+    pub idx: usize,
+
+    /// The `Client::last_focused` stamps of the clients when the cycle started. They are restored
+    /// when the cycle ends, so the clients selected on the way do not change the focus history.
+    pub stamps: Vec<(ClientID, u64)>,
+}
+
+impl FocusCycle {
+    /// Creates a new cycle through `clients`, ordered by their focus history.
+    pub fn new(modifier: u16, clients: &[&Client]) -> Self {
+        Self {
+            modifier,
+            clients: clients.iter().map(|c| c.id).collect(),
+            idx: 0,
+            stamps: clients.iter().map(|c| (c.id, c.last_focused)).collect(),
+        }
+    }
+}
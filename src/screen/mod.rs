@@ -1,15 +1,27 @@
+mod cycle;
+mod selection;
+
 use std::sync::Arc;
 
 use xcb_util::ewmh;
 
 use crate::{
+    screen::cycle::FocusCycle,
     tag::{
-        Tag, TagID,
+        Tag, TagID, TagKind, TagGeometry,
+    },
+    monitor::{
+        self,
+        Monitor,
+        MonitorID,
+        MonitorGeometry,
     },
     errors::Error,
-    client::Client,
-    util,
+    client::{Client, ClientAction, ClientID, ClientState, StackLayer},
+    util::{self, math, Direction, Operation},
     layout::LayoutTile,
+    ipc,
+    config::Config,
 };
 
 #[derive(Clone)]
@@ -23,39 +35,78 @@ pub struct Screen {
 
     pub root: u32,
 
+    /// An unmapped child of the root window used by other clients to detect that a compliant
+    /// window manager is running.
+    /// See: https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142919904
+    pub check_window: u32,
+
+    /// The ICCCM manager selection (`WM_S<id>`) owned by `check_window`. Losing it means that
+    /// another window manager is replacing SapphireWM.
+    pub selection: xcb::Atom,
+
     pub geo: ScreenGeometry,
 
-    /// ID of the currently focused tag. Retrieve the current tag using `Self::get_focused_tag[mut]()`,
-    /// as the ID may point to an non existent tag.
-    focused_tag_id: TagID,
+    /// ID of the currently focused monitor. New clients are managed on the focused tag of this
+    /// monitor.
+    focused_monitor_id: MonitorID,
 
     /// The ewmh connection.
     conn: Arc<ewmh::Connection>,
 
-    /// Stores information about all the tags in the window manager. Each tag is responsible for
-    /// managing its own clients. This vector is never empty, and must store at least 1 valid tag.
-    ///
-    /// The last tag in the vector is reserved for storing "sticky" clients. `Sticky` clients are
-    /// those that the window manager must keep on the screen even when changing tags, such as
-    /// docks, SapphireWM always ensures that this tag exists and does not allow "normal" clients
-    /// (e.g., terminals) to be in sticky mode.
+    /// Stores information about all the monitors of the screen. Each monitor is responsible for
+    /// managing its own tags. This vector is never empty, and must store at least 1 valid monitor.
+    monitors: Vec<Monitor>,
+
+    /// Reserved for storing "sticky" clients. `Sticky` clients are those that the window manager
+    /// must keep on the screen even when changing tags, such as docks or picture-in-picture
+    /// windows. SapphireWM always ensures that this tag exists, and "normal" clients (e.g.,
+    /// terminals) are floating while in sticky mode. The tag is shared by all monitors.
     /// See: https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142867040
-    /// Use either `Manager::sticky_tag()` or `Manager::sticky_tag_mut()` to retrieve such
-    /// clients.
-    tags: Vec<Tag>,
+    /// Use `Screen::sticky_tag()` to retrieve such clients.
+    sticky: Tag,
+
+    /// IDs of all managed clients, from the bottom to the top of the stack. The order is only
+    /// kept within a stacking layer, as higher layers are always stacked above lower ones; see
+    /// `Screen::restack`.
+    stack: Vec<ClientID>,
+
+    /// The clients of the scratchpads, by the name of the scratchpad. See `Screen::toggle_scratchpad`.
+    scratchpads: Vec<(String, ClientID)>,
+
+    /// IDs of the clients that demanded attention, from the oldest to the most recent. It may
+    /// contain clients that are no longer urgent; see `Screen::update_urgent_tags`.
+    urgent_clients: Vec<ClientID>,
+
+    /// The alt-tab style cycle through the focus history in progress, if any.
+    focus_cycle: Option<FocusCycle>,
+
+    /// ID of the tag whose focused client holds the input focus when it is not the focused tag,
+    /// i.e. the sticky tag or another tag shown by the focused monitor. See `Screen::focus_client`.
+    focus_owner: Option<TagID>,
 }
 
 impl Screen {
-    pub fn new(conn: Arc<ewmh::Connection>, id: i32, screen: xcb::Screen) -> Self {
+    /// Creates the screen and starts managing it. The ICCCM `WM_S<id>` selection is acquired
+    /// first; when another window manager is running it is only replaced if `replace` is `true`.
+    /// Returns an error when the screen cannot be managed.
+    pub fn new(conn: Arc<ewmh::Connection>, id: i32, screen: xcb::Screen, replace: bool) -> Result<Self, Error> {
+        let check_window = Self::create_check_window(&conn, screen.root());
+        let selection = selection::acquire(&conn, id, screen.root(), check_window, replace)?;
+
+        // The root property is only set once the previous window manager, if any, released the
+        // screen, as it deletes the property when exiting.
+        ewmh::set_supporting_wm_check(&conn, screen.root(), check_window);
+
         if let Err(cookie) = xcb::change_window_attributes_checked(
             &conn,
             screen.root(),
             &[(
                 xcb::CW_EVENT_MASK,
-                xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT | xcb::EVENT_MASK_SUBSTRUCTURE_NOTIFY,
+                // Property changes on the root are used to receive IPC commands.
+                xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT | xcb::EVENT_MASK_SUBSTRUCTURE_NOTIFY | xcb::EVENT_MASK_PROPERTY_CHANGE,
             )],
         ).request_check() {
-            panic!("Is another window manager running? Error = {}", cookie)
+            return Err(Error::Custom(format!("Unable to manage the screen. Is another window manager running? Error = {}", cookie)))
         }
 
         ewmh::set_supported(
@@ -64,23 +115,38 @@ impl Screen {
             &[
                 conn.SUPPORTED(),
                 conn.SUPPORTING_WM_CHECK(),
+                conn.WM_NAME(),
 
                 conn.CLIENT_LIST(),
+                conn.CLIENT_LIST_STACKING(),
+                conn.CLOSE_WINDOW(),
+                conn.MOVERESIZE_WINDOW(),
+                conn.RESTACK_WINDOW(),
 
                 conn.ACTIVE_WINDOW(),
                 conn.CURRENT_DESKTOP(),
                 conn.DESKTOP_NAMES(),
                 conn.NUMBER_OF_DESKTOPS(),
+                conn.DESKTOP_GEOMETRY(),
+                conn.DESKTOP_VIEWPORT(),
+                conn.WORKAREA(),
 
                 conn.WM_STATE(),
                 conn.WM_STATE_FULLSCREEN(),
                 conn.WM_STATE_MAXIMIZED_VERT(),
                 conn.WM_STATE_MAXIMIZED_HORZ(),
                 conn.WM_STATE_STICKY(),
+                conn.WM_STATE_HIDDEN(),
+                conn.WM_STATE_DEMANDS_ATTENTION(),
+                conn.WM_STATE_ABOVE(),
+                conn.WM_STATE_BELOW(),
+
+                conn.WM_DESKTOP(),
 
                 conn.WM_WINDOW_TYPE(),
                 conn.WM_WINDOW_TYPE_DOCK(),
                 conn.WM_WINDOW_TYPE_NORMAL(),
+                conn.WM_WINDOW_TYPE_DESKTOP(),
 
                 conn.WM_ACTION_FULLSCREEN(),
                 conn.WM_ACTION_MAXIMIZE_VERT(),
@@ -89,7 +155,7 @@ impl Screen {
                 conn.WM_ACTION_CHANGE_DESKTOP(),
                 conn.WM_ACTION_RESIZE(),
                 conn.WM_ACTION_MOVE(),
-                // conn.WM_ACTION_MINIMIZE(), 
+                conn.WM_ACTION_MINIMIZE(),
 
                 conn.WM_STRUT(),
                 conn.WM_STRUT_PARTIAL(),
@@ -98,7 +164,7 @@ impl Screen {
             ],
         );
 
-        let tags = vec![
+        let aliases = vec![
             String::from("1"),
             String::from("2"),
             String::from("3"),
@@ -110,37 +176,88 @@ impl Screen {
             String::from("9"),
         ];
 
-        // The screen must have at least one tag.
-        let mut tags = if !tags.is_empty() {
-            tags
-                .iter()
-                .enumerate()
-                .map(|(i, a)| Tag::new(conn.clone(), i as u32, a, screen.width_in_pixels() as u32, screen.height_in_pixels() as u32))
-                .collect()
-        } else {
-            vec![Tag::new(conn.clone(), 0, "1", screen.width_in_pixels() as u32, screen.height_in_pixels() as u32)]
-        };
-    
-        ewmh::set_number_of_desktops(&conn, id, tags.len() as u32);
-        ewmh::set_desktop_names(&conn, id, tags.iter().map(|d| d.alias.as_ref()));
+        let width = screen.width_in_pixels() as u32;
+        let height = screen.height_in_pixels() as u32;
+
+        // Tag IDs are unique across monitors, so that each tag maps to exactly one EWMH desktop.
+        let monitors: Vec<Monitor> = monitor::detect(&conn, screen.root(), width, height)
+            .into_iter()
+            .enumerate()
+            .map(|(i, info)| Monitor::new(conn.clone(), i, info, &aliases, (i * aliases.len()) as u32))
+            .collect();
+
+        let tags_len = monitors.iter().map(|m| m.tags().count()).sum::<usize>();
+        ewmh::set_number_of_desktops(&conn, id, tags_len as u32);
+        ewmh::set_desktop_names(&conn, id, monitors.iter().flat_map(|m| m.tags()).map(|t| t.alias.as_ref()));
 
         // Create the sticky tag.
         // Since the clients contained in this tag will be shown in all other tags the ID of the
         // tag must be set to the maximum 32-bit number.
         // Reference: https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46201142872912
-        tags.push(Tag::new(conn.clone(), 0xFFFFFFFF, "sticky_clients", 0, 0));
+        let sticky = Tag::new(conn.clone(), 0xFFFFFFFF, "sticky_clients", TagKind::Sticky, TagGeometry::new(0, 0, 0, 0, [0, 0, 0, 0]));
 
-        Self {
+        let screen = Self {
             id,
+            check_window,
+            selection,
             root: screen.root(),
             conn,
-            tags,
-            focused_tag_id: 0, // TODO: config.default_focused_tag_id
+            monitors,
+            sticky,
+            stack: Vec::new(),
+            urgent_clients: Vec::new(),
+            focus_cycle: None,
+            focus_owner: None,
+            scratchpads: Vec::new(),
+            focused_monitor_id: 0,
             geo: ScreenGeometry {
-                width: screen.width_in_pixels() as u32,
-                height: screen.height_in_pixels() as u32,
+                width,
+                height,
             },
+        };
+
+        screen.update_workarea();
+        Ok(screen)
+    }
+
+    /// Creates the "_NET_SUPPORTING_WM_CHECK" window, an unmapped child of the root window, and
+    /// sets the property on it. The window is also identified with a "_NET_WM_NAME". The property
+    /// of the root window is set by `Screen::new`, after acquiring the manager selection.
+    fn create_check_window(conn: &ewmh::Connection, root: u32) -> u32 {
+        let window = conn.generate_id();
+
+        xcb::create_window(
+            conn,
+            xcb::COPY_FROM_PARENT as u8,
+            window,
+            root,
+            -1,
+            -1,
+            1,
+            1,
+            0,
+            xcb::WINDOW_CLASS_INPUT_ONLY as u16,
+            xcb::COPY_FROM_PARENT,
+            &[(xcb::CW_OVERRIDE_REDIRECT, 1)],
+        );
+
+        ewmh::set_supporting_wm_check(conn, window, window);
+        ewmh::set_wm_name(conn, window, "SapphireWM");
+
+        window
+    }
+
+    /// Stops managing the screen, e.g. when another window manager replaces SapphireWM. All
+    /// clients are mapped, so that they remain reachable by the next window manager, and the
+    /// "_NET_SUPPORTING_WM_CHECK" window is destroyed.
+    pub fn release(&self) {
+        for t in self.tags() {
+            t.clone_clients().iter().for_each(|c| c.map(&self.conn));
         }
+
+        xcb::delete_property(&self.conn, self.root, self.conn.SUPPORTING_WM_CHECK());
+        xcb::destroy_window(&self.conn, self.check_window);
+        self.conn.flush();
     }
 
     /// Sets the default screen and tag for the window manager.
@@ -150,183 +267,1520 @@ impl Screen {
 }
 
 impl Screen {
-    fn set_focused_tag(&mut self, tag_id: TagID) {
+    /// Sets the tag with ID `tag_id` as the focused tag of its monitor and the monitor as the
+    /// focused one.
+    fn set_focused_tag(&mut self, tag_id: TagID) -> Result<(), Error> {
+        let monitor = self.get_monitor_of_tag_mut(tag_id)?;
+        monitor.set_focused_tag_id(tag_id)?;
+
+        self.focused_monitor_id = monitor.id;
+        self.focus_owner = None;
         ewmh::set_current_desktop(&self.conn, self.id, tag_id);
-        self.focused_tag_id = tag_id;
+
+        Ok(())
     }
 
     pub fn contains_tag(&self, tag_id: TagID) -> bool {
-        self.tags.iter().any(|t| t.id == tag_id)
+        self.sticky.id == tag_id || self.monitors.iter().any(|m| m.contains_tag(tag_id))
     }
 
     /// Returns an immutable reference to the sticky tag.
     pub fn sticky_tag(&self) -> &Tag {
-        // As the window manager ensures that this tag always exists, it will never be `None`.
-        let idx = self.tags.len()-1;
-        self.tags.get(idx).unwrap()
+        &self.sticky
     }
 
-    /// Returns a mutable reference to the sticky tag.
-    pub fn sticky_tag_mut(&mut self) -> &mut Tag {
-        // As the window manager ensures that this tag always exists, it will never be `None`.
-        let idx = self.tags.len()-1;
-        self.tags.get_mut(idx).unwrap()
+    /// Returns an iterator over all tags of all monitors. It does not include the sticky tag.
+    pub fn tags(&self) -> impl Iterator<Item = &Tag> {
+        self.monitors.iter().flat_map(|m| m.tags())
     }
 
-    /// Returns a immutable reference to the specified tag or `Error::TagNotFound(id)` when the
-    /// provided ID does not exist.
-    pub fn get_tag(&self, id: u32) -> Result<&Tag, Error> {
-        self.tags
+    /// Returns a immutable reference to the specified monitor or `Error::MonitorNotFound(id)` when
+    /// the provided ID does not exist.
+    pub fn get_monitor(&self, id: MonitorID) -> Result<&Monitor, Error> {
+        self.monitors.get(id).ok_or(Error::MonitorNotFound(id))
+    }
+
+    /// Returns a mutable reference to the specified monitor or `Error::MonitorNotFound(id)` when
+    /// the provided ID does not exist.
+    pub fn get_monitor_mut(&mut self, id: MonitorID) -> Result<&mut Monitor, Error> {
+        self.monitors.get_mut(id).ok_or(Error::MonitorNotFound(id))
+    }
+
+    /// Returns an immutable reference to the focused monitor.
+    pub fn get_focused_monitor(&self) -> &Monitor {
+        // As the window manager ensures that the focused monitor always exists, it will never be `None`.
+        self.monitors.get(self.focused_monitor_id).unwrap()
+    }
+
+    /// Returns a mutable reference to the focused monitor.
+    pub fn get_focused_monitor_mut(&mut self) -> &mut Monitor {
+        // As the window manager ensures that the focused monitor always exists, it will never be `None`.
+        self.monitors.get_mut(self.focused_monitor_id).unwrap()
+    }
+
+    /// Returns an immutable reference to the monitor that contains the tag with ID `id`. Returns
+    /// `Error::TagNotFound(id)` when no monitor contains the tag.
+    pub fn get_monitor_of_tag(&self, id: TagID) -> Result<&Monitor, Error> {
+        self.monitors
             .iter()
-            .find(|t| t.id == id)
+            .find(|m| m.contains_tag(id))
             .ok_or(Error::TagNotFound(id))
     }
 
-    /// Returns a mutable reference to the specified tag or `Error::TagNotFound(id)` when the
-    /// provided ID does not exist.
-    pub fn get_tag_mut(&mut self, id: u32) -> Result<&mut Tag, Error> {
-        self.tags
+    /// Returns a mutable reference to the monitor that contains the tag with ID `id`. Returns
+    /// `Error::TagNotFound(id)` when no monitor contains the tag.
+    pub fn get_monitor_of_tag_mut(&mut self, id: TagID) -> Result<&mut Monitor, Error> {
+        self.monitors
             .iter_mut()
-            .find(|t| t.id == id)
+            .find(|m| m.contains_tag(id))
             .ok_or(Error::TagNotFound(id))
     }
 
-    /// Returns a immutable reference to the focused tag or `Error::TagNotFound(id)` when the
+    /// Returns an immutable reference to the monitor that contains the point `(x, y)`, relative to
+    /// the root window, if any.
+    pub fn get_monitor_at(&self, x: u32, y: u32) -> Option<&Monitor> {
+        self.monitors.iter().find(|m| m.geo.contains(x, y))
+    }
+
+    /// Focuses the monitor with ID `id`. It will also set the input focus to the focused client on
+    /// the visible tag of the monitor, if any. Returns `Error::MonitorNotFound(id)` when the
     /// provided ID does not exist.
-    pub fn get_focused_tag(&self) -> Result<&Tag, Error> {
-        let id = self.focused_tag_id;
+    pub fn focus_monitor(&mut self, id: MonitorID) -> Result<(), Error> {
+        if self.focused_monitor_id == id {
+            return Ok(())
+        }
+
+        let conn = self.conn.clone();
+        let monitor = self.get_monitor_mut(id)?;
+
+        match monitor.get_focused_tag_mut()?.get_focused_client_mut() {
+            Ok(c) => c.set_input_focus(&conn),
+            Err(_) => util::disable_input_focus(&conn),
+        }
+
+        let tag_id = monitor.focused_tag_id();
+        self.set_focused_tag(tag_id)
+    }
+
+    /// Returns the ID of a monitor by its relative index to the focused monitor. Walking beyond the
+    /// last monitor loops back to the first one. Returns `None` when there is a single monitor.
+    pub fn get_monitor_byidx(&self, i: i32) -> Option<MonitorID> {
+        math::cycle_idx(self.monitors.len(), self.focused_monitor_id as i32 + i)
+    }
+
+    /// Returns the ID of the closest monitor in the direction `dir` of the focused monitor, if any.
+    pub fn get_monitor_in_direction(&self, dir: Direction) -> Option<MonitorID> {
+        let geo = &self.get_focused_monitor().geo;
+        let (x, y) = geo.center();
 
-        self.tags
+        self.monitors
             .iter()
-            .find(|t| t.id == id)
-            .ok_or(Error::TagNotFound(id))
+            .filter(|m| geo.is_in_direction(dir, &m.geo))
+            .min_by_key(|m| {
+                let (mx, my) = m.geo.center();
+                (mx - x).abs() + (my - y).abs()
+            })
+            .map(|m| m.id)
+    }
+
+    /// Focuses a monitor by its relative index to the focused monitor. See `Screen::get_monitor_byidx`.
+    pub fn focus_monitor_byidx(&mut self, i: i32) -> Result<(), Error> {
+        match self.get_monitor_byidx(i) {
+            Some(id) => self.focus_monitor(id),
+            None => Ok(()),
+        }
+    }
+
+    /// Focuses the closest monitor in the direction `dir` of the focused monitor, if any.
+    pub fn focus_monitor_in_direction(&mut self, dir: Direction) -> Result<(), Error> {
+        match self.get_monitor_in_direction(dir) {
+            Some(id) => self.focus_monitor(id),
+            None => Ok(()),
+        }
+    }
+
+    /// Focuses the closest client in the direction `dir` of the focused client, among the clients
+    /// of every tag viewed by the focused monitor. When there is no client in the direction, the
+    /// closest monitor in the direction is focused, if any.
+    pub fn focus_client_in_direction(&mut self, dir: Direction) -> Result<(), Error> {
+        let target = self.get_focused_client()
+            .ok()
+            .and_then(|c| self.get_focused_monitor().get_client_in_direction(dir, c))
+            .map(|c| c.id);
+
+        match target {
+            Some(id) => self.focus_client(id),
+            None => self.focus_monitor_in_direction(dir),
+        }
+    }
+
+    /// Swaps the focused client with the closest client in the direction `dir`, among the clients
+    /// of every tag viewed by the focused monitor, exchanging their positions in the layout. When
+    /// there is no client in the direction, the focused client is moved to the visible tag of the
+    /// closest monitor in the direction, if any, and the focus follows it.
+    pub fn swap_client_in_direction(&mut self, dir: Direction) -> Result<(), Error> {
+        let tag_id = self.focused_client_tag_id()?;
+
+        // Sticky clients float and have no position in the layout.
+        if tag_id == self.sticky.id {
+            return Ok(())
+        }
+
+        let focused = self.get_focused_client()?;
+        let focused_id = focused.id;
+
+        if let Some(id) = self.get_focused_monitor().get_client_in_direction(dir, focused).map(|c| c.id) {
+            self.swap_clients(focused_id, id)?;
+            self.refresh();
+
+            return Ok(())
+        }
+
+        let monitor_id = match self.get_monitor_in_direction(dir) {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+
+        let dest = self.get_monitor(monitor_id)?.focused_tag_id();
+        self.move_focused_client(tag_id, dest)?;
+        self.focus_monitor(monitor_id)?;
+        self.refresh();
+
+        Ok(())
+    }
+
+    /// Exchanges the positions in the layout of the clients with IDs `a` and `b`, which must be on
+    /// the same monitor. Clients of different tags take the place of each other entirely,
+    /// including the tags they are shown on. The focus stays on the focused client.
+    fn swap_clients(&mut self, a: ClientID, b: ClientID) -> Result<(), Error> {
+        let tag_a = self.get_tag_of_client(a)?.id;
+        let tag_b = self.get_tag_of_client(b)?.id;
+
+        if tag_a == tag_b {
+            self.get_tag_mut(tag_a)?.swap(a, b);
+            return self.arrange_tag(tag_a)
+        }
+
+        let focused_id = self.get_focused_client().map(|c| c.id).ok();
+
+        let mut client_a = self.get_tag(tag_a)?.get_client(a)?.clone();
+        let mut client_b = self.get_tag(tag_b)?.get_client(b)?.clone();
+        std::mem::swap(&mut client_a.tag_mask, &mut client_b.tag_mask);
+
+        self.get_tag_mut(tag_b)?.replace_client(b, client_a)?;
+        self.get_tag_mut(tag_a)?.replace_client(a, client_b)?;
+
+        util::set_client_tag(&self.conn, a, tag_b);
+        util::set_client_tag(&self.conn, b, tag_a);
+
+        // The focused client may now be managed by another tag than the focused one.
+        if let Some(id) = focused_id.filter(|id| *id == a || *id == b) {
+            self.focus_owner = None;
+            self.focus_client(id)?;
+        }
+
+        _ = self.arrange_tag(tag_b);
+        self.arrange_tag(tag_a)
+    }
+
+    /// Moves the focused client to the monitor with ID `id`, placing it on the tag with the same
+    /// index as its current tag. The focus stays on the focused monitor. Returns
+    /// `Error::MonitorNotFound(id)` when the provided ID does not exist.
+    pub fn move_focused_client_to_monitor(&mut self, id: MonitorID) -> Result<(), Error> {
+        if self.focused_monitor_id == id {
+            return Ok(())
+        }
+
+        // The focused client may belong to another viewed tag than the focused one. Sticky clients
+        // are shown on every monitor, so they are not moved.
+        let src = self.focused_client_tag_id()?;
+        if src == self.sticky.id {
+            return Ok(())
+        }
+
+        let src_monitor = self.get_focused_monitor();
+        let idx = src_monitor.tags().position(|t| t.id == src).unwrap_or(0);
+
+        let dest_monitor = self.get_monitor(id)?;
+        let dest = dest_monitor.get_tag_id(idx.min(dest_monitor.tags().count() - 1))?;
+
+        self.move_focused_client(src, dest)?;
+
+        // `Screen::move_focused_client` gives the input focus to the moved client, so it must be
+        // restored to the source tag.
+        self.focus_focused_client();
+
+        Ok(())
+    }
+
+    /// Exchanges the clients of the visible tags of the monitors with IDs `a` and `b`. Returns
+    /// `Error::MonitorNotFound(a|b)` when any provided ID does not exist.
+    pub fn swap_visible_tags(&mut self, a: MonitorID, b: MonitorID) -> Result<(), Error> {
+        let tag_a = self.get_monitor(a)?.focused_tag_id();
+        let tag_b = self.get_monitor(b)?.focused_tag_id();
+
+        if a == b {
+            return Ok(())
+        }
+
+        let (low, high) = (a.min(b), a.max(b));
+        let paddings_low = self.sticky_paddings(self.get_monitor(low)?);
+        let paddings_high = self.sticky_paddings(self.get_monitor(high)?);
+
+        let (left, right) = self.monitors.split_at_mut(high);
+        let (geo_low, geo_high) = (left[low].geo.clone(), right[0].geo.clone());
+
+        let t_low = left[low].get_focused_tag_mut()?;
+        let t_high = right[0].get_focused_tag_mut()?;
+        t_low.swap_clients(t_high);
+
+        // Floating clients keep their position relative to the monitor.
+        t_low
+            .clients_mut()
+            .for_each(|c| Self::translate_floating(c, &geo_high, &geo_low, paddings_low));
+        t_high
+            .clients_mut()
+            .for_each(|c| Self::translate_floating(c, &geo_low, &geo_high, paddings_high));
+
+        for id in [tag_a, tag_b] {
+            self.get_tag(id)?
+                .clone_clients()
+                .iter()
+                .for_each(|c| util::set_client_tag(&self.conn, c.id, id));
+
+            _ = self.arrange_tag(id);
+        }
+
+        // The focused client of the focused tag may have changed.
+        self.focus_focused_client();
+
+        Ok(())
     }
 
-    /// Returns a mutable reference to the focused tag or `Error::TagNotFound(id)` when the
+    /// Returns a immutable reference to the specified tag or `Error::TagNotFound(id)` when the
     /// provided ID does not exist.
-    pub fn get_focused_tag_mut(&mut self) -> Result<&mut Tag, Error> {
-        let id = self.focused_tag_id;
+    pub fn get_tag(&self, id: u32) -> Result<&Tag, Error> {
+        if self.sticky.id == id {
+            return Ok(&self.sticky)
+        }
 
-        self.tags
-            .iter_mut()
-            .find(|t| t.id == id)
-            .ok_or(Error::TagNotFound(id))
+        self.get_monitor_of_tag(id)?.get_tag(id)
     }
 
-    /// Readjust the layout of the tag with ID `id`. Returns `Error::TagNotFound(id)` when the
+    /// Returns a mutable reference to the specified tag or `Error::TagNotFound(id)` when the
     /// provided ID does not exist.
+    pub fn get_tag_mut(&mut self, id: u32) -> Result<&mut Tag, Error> {
+        if self.sticky.id == id {
+            return Ok(&mut self.sticky)
+        }
+
+        self.get_monitor_of_tag_mut(id)?.get_tag_mut(id)
+    }
+
+    /// Returns an immutable reference to the tag that manages the client with ID `id`, including
+    /// the sticky tag. Returns `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn get_tag_of_client(&self, id: ClientID) -> Result<&Tag, Error> {
+        self.tags()
+            .chain(std::iter::once(&self.sticky))
+            .find(|t| t.contains_client(id))
+            .ok_or(Error::ClientNotFound(id))
+    }
+
+    /// Returns a mutable reference to the tag that manages the client with ID `id`, including the
+    /// sticky tag. Returns `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn get_tag_of_client_mut(&mut self, id: ClientID) -> Result<&mut Tag, Error> {
+        self.monitors
+            .iter_mut()
+            .flat_map(|m| m.tags_mut())
+            .chain(std::iter::once(&mut self.sticky))
+            .find(|t| t.contains_client(id))
+            .ok_or(Error::ClientNotFound(id))
+    }
+
+    /// Returns a immutable reference to the focused tag of the focused monitor or
+    /// `Error::TagNotFound(id)` when the provided ID does not exist.
+    pub fn get_focused_tag(&self) -> Result<&Tag, Error> {
+        self.get_focused_monitor().get_focused_tag()
+    }
+
+    /// Returns a mutable reference to the focused tag of the focused monitor or
+    /// `Error::TagNotFound(id)` when the provided ID does not exist.
+    pub fn get_focused_tag_mut(&mut self) -> Result<&mut Tag, Error> {
+        self.get_focused_monitor_mut().get_focused_tag_mut()
+    }
+
+    /// Readjust the layout of the tag with ID `id`. As states such as fullscreen change the
+    /// stacking layer of clients, the clients are also restacked. Returns `Error::TagNotFound(id)`
+    /// when the provided ID does not exist.
     pub fn arrange_tag(&mut self, id: TagID) -> Result<(), Error> {
-        let sticky = self.sticky_tag_mut().clone();
+        let paddings = self.sticky_paddings(self.get_monitor_of_tag(id)?);
+        let monitor = self.get_monitor_of_tag_mut(id)?;
+
+        if !monitor.is_tag_viewed(id) {
+            monitor.get_tag_mut(id)?.arrange(&LayoutTile::new(), paddings);
+        }
+
+        // A viewed tag is arranged together with the other tags viewed by its monitor, which may
+        // also show clients of hidden tags through their additional tags.
+        monitor.arrange(&LayoutTile::new(), paddings);
 
-        self.get_tag_mut(id)?
-            .arrange(&LayoutTile::new(), &sticky);
+        self.restack();
 
         Ok(())
     }
 
-    /// Focuses and view the tag with ID `id`. It will also set the input focus to the focused
-    /// client on the tag, if any. Returns `Error::TagNotFound(id)` when the provided ID does not
-    /// exist. 
+    /// Readjust the layout of the visible tag of every monitor.
+    pub fn arrange_monitors(&mut self) {
+        let visible: Vec<TagID> = self.monitors.iter().map(|m| m.focused_tag_id()).collect();
+        for id in visible {
+            _ = self.arrange_tag(id);
+        }
+    }
+
+    /// Readjusts the layout affected by the clients of the tag with ID `id`. The clients of the
+    /// sticky tag may reserve space (e.g. docks) on every monitor, so all monitors and the work
+    /// area are updated in that case.
+    pub fn arrange_affected_by(&mut self, id: TagID) {
+        if id == self.sticky.id {
+            self.arrange_monitors();
+            self.update_workarea();
+        } else {
+            _ = self.arrange_tag(id);
+        }
+    }
+
+    /// Returns the paddings reserved on `monitor` by the struts of all sticky clients (e.g. docks),
+    /// following the same order as `TagGeometry::paddings`.
+    fn sticky_paddings(&self, monitor: &Monitor) -> [u32; 4] {
+        self.sticky
+            .clone_clients()
+            .iter()
+            .map(|c| monitor.geo.paddings_from_struts(self.geo.width, self.geo.height, &c.geo))
+            .fold([0, 0, 0, 0], |acc, p| {
+                [acc[0].max(p[0]), acc[1].max(p[1]), acc[2].max(p[2]), acc[3].max(p[3])]
+            })
+    }
+
+    /// Keeps a floating client moved from the monitor with geometry `from` to the monitor with
+    /// geometry `to` at the same position relative to the monitor, inside the area not reserved
+    /// by `paddings`. Other clients are placed by the layout.
+    fn translate_floating(client: &mut Client, from: &MonitorGeometry, to: &MonitorGeometry, paddings: [u32; 4]) {
+        if client.has_state(&ClientState::Floating) {
+            to.translate_client(from, paddings, &mut client.geo);
+        }
+    }
+
+    /// Updates the "_NET_DESKTOP_GEOMETRY", "_NET_DESKTOP_VIEWPORT" and "_NET_WORKAREA" of all
+    /// tags. The work area of a tag is the area of its monitor that is not reserved by struts.
+    /// Must be called whenever the struts or the monitors change.
+    pub fn update_workarea(&self) {
+        ewmh::set_desktop_geometry(&self.conn, self.id, self.geo.width, self.geo.height);
+
+        // SapphireWM does not support large desktops, so every viewport is at the origin.
+        let viewports: Vec<ewmh::Coordinates> = self.tags()
+            .map(|_| ewmh::Coordinates { x: 0, y: 0 })
+            .collect();
+
+        ewmh::set_desktop_viewport(&self.conn, self.id, &viewports);
+
+        let mut workarea: Vec<u32> = Vec::new();
+        for m in self.monitors.iter() {
+            let paddings = self.sticky_paddings(m);
+
+            for t in m.tags() {
+                let geo = TagGeometry::new(
+                    t.geo.x,
+                    t.geo.y,
+                    t.geo.w,
+                    t.geo.h,
+                    [
+                        t.geo.padding_top().max(paddings[0]),
+                        t.geo.padding_bottom().max(paddings[1]),
+                        t.geo.padding_left().max(paddings[2]),
+                        t.geo.padding_right().max(paddings[3]),
+                    ],
+                );
+
+                workarea.extend([geo.x + geo.padding_left(), geo.y + geo.padding_top(), geo.avail_w, geo.avail_h]);
+            }
+        }
+
+        xcb::change_property(
+            &self.conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            self.root,
+            self.conn.WORKAREA(),
+            xcb::ATOM_CARDINAL,
+            32,
+            workarea.as_slice(),
+        );
+    }
+
+    /// Focuses and view the tag with ID `id`, focusing its monitor as well. It will also set the
+    /// input focus to the focused client on the tag, if any. Returns `Error::TagNotFound(id)` when
+    /// the provided ID does not exist.
     pub fn view_tag(&mut self, id: u32) -> Result<(), Error> {
-        if self.get_focused_tag().is_ok_and(|t| t.id == id) {
+        let monitor = self.get_monitor_of_tag(id)?;
+        let bit = monitor.tag_bit(id);
+
+        if self.get_focused_tag().is_ok_and(|t| t.id == id) && monitor.view_mask() == bit {
             return Ok(())
         }
 
+        let monitor_id = monitor.id;
         let conn = self.conn.clone();
 
-        let tag = self.get_tag_mut(id)?;
-        tag.map();
-        
+        // This follows the second approach outlined in the "Virtual Desktop Implementation note" (https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm45912241712208)
+        // of the EWMH.
+        self.set_view(monitor_id, id, bit)?;
+
         // Set the input focus to the currently focused client on dtag, if one exists; otherwise
         // disable the input.
-        match tag.get_focused_client_mut() {
+        match self.get_tag_mut(id)?.get_focused_client_mut() {
             Ok(c) => c.set_input_focus(&conn),
             Err(_) => util::disable_input_focus(&conn),
         }
 
-        // Before updating the ID of the focused tag, we hide all visible clients on the current
-        // focused tag, if any.
-        //
-        // This follows the second approach outlined in the "Virtual Desktop Implementation note" (https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm45912241712208)
-        // of the EWMH.
-        if let Ok(tag) = self.get_focused_tag() {
-            tag.unmap();
+        _ = self.arrange_tag(id);
+        self.set_focused_tag(id)
+    }
+
+    /// Views the tag focused before the focused tag of the focused monitor, if any. Calling it
+    /// again goes back and forth between both tags.
+    pub fn view_previous_tag(&mut self) -> Result<(), Error> {
+        match self.get_focused_monitor().previous_tag_id() {
+            Some(id) => self.view_tag(id),
+            None => Ok(()),
         }
+    }
 
-        _ = self.arrange_tag(id);
-        self.set_focused_tag(id);
+    /// Same as `Screen::view_tag()`, but when the tag with ID `id` is already the focused tag and
+    /// `Config::tag_back_and_forth` is set, the previously focused tag is viewed instead.
+    pub fn view_tag_back_and_forth(&mut self, id: TagID) -> Result<(), Error> {
+        let is_focused = self.get_focused_tag().is_ok_and(|t| t.id == id);
 
-        Ok(())
+        if is_focused && Config::current().tag_back_and_forth {
+            return self.view_previous_tag()
+        }
+
+        self.view_tag(id)
     }
 
-    /// Moves the currently focused client from the source tag to destination tag. Returns
-    /// `Error::TagNotFound(src|dest)` when any provided ID does not exist.
-    pub fn move_focused_client(&mut self, src: TagID, dest: TagID) -> Result<(), Error> {
-        if !self.contains_tag(src) {
-            return Err(Error::TagNotFound(src))
+    /// Toggles whether the tag with ID `id` is viewed by its monitor together with the other
+    /// viewed tags, dwm style. The last viewed tag of a monitor cannot be hidden. When the focused
+    /// tag of the monitor is hidden, the first remaining viewed tag becomes the focused one.
+    /// Returns `Error::TagNotFound(id)` when the tag does not exist.
+    pub fn toggle_view_tag(&mut self, id: TagID) -> Result<(), Error> {
+        let monitor = self.get_monitor_of_tag(id)?;
+        let mask = monitor.view_mask() ^ monitor.tag_bit(id);
+
+        if mask == 0 {
+            return Ok(())
         }
 
-        if !self.contains_tag(dest) {
-            return Err(Error::TagNotFound(dest))
+        let monitor_id = monitor.id;
+        let is_focused_monitor = monitor_id == self.focused_monitor_id;
+
+        let focused = match monitor.focused_tag_id() {
+            f if f != id || mask & monitor.tag_bit(f) != 0 => f,
+            _ => monitor.tags().find(|t| mask & monitor.tag_bit(t.id) != 0).map(|t| t.id).ok_or(Error::TagNotFound(id))?,
+        };
+
+        self.set_view(monitor_id, focused, mask)?;
+        _ = self.arrange_tag(focused);
+
+        if is_focused_monitor {
+            self.set_focused_tag(focused)?;
+            self.focus_focused_client();
         }
 
-        let conn = self.conn.clone();
+        Ok(())
+    }
+
+    /// Views all tags of the focused monitor at once, keeping the focused tag.
+    pub fn view_all_tags(&mut self) -> Result<(), Error> {
+        let monitor = self.get_focused_monitor();
+        let (monitor_id, focused, mask) = (monitor.id, monitor.focused_tag_id(), monitor.all_tags_mask());
 
-        // Unmanage and hide the focused client of the source tag.
-        let s_tag = self.get_tag_mut(src).unwrap();
+        self.set_view(monitor_id, focused, mask)?;
+        self.arrange_tag(focused)
+    }
+
+    /// Toggles whether the client with ID `id` is also shown on the tag with ID `tag_id`, dwm
+    /// style. The tag must be on the monitor of the client. Removing the client from the tag that
+    /// manages it moves the client to another of its tags; a client always keeps at least one tag.
+    /// Returns `Error::ClientNotFound(id)` when no tag manages the client and
+    /// `Error::InvalidOperation` when the tag is on another monitor or the client is sticky.
+    pub fn toggle_client_tag(&mut self, id: ClientID, tag_id: TagID) -> Result<(), Error> {
+        let src = self.get_tag_of_client(id)?.id;
+        if src == self.sticky.id {
+            return Err(Error::InvalidOperation)
+        }
 
-        let client = s_tag.get_focused_client_mut().unwrap().clone();
-        client.unmap(&conn);
-        let client_id = client.id;
+        let monitor = self.get_monitor_of_tag(src)?;
+        let bit = monitor.tag_bit(tag_id);
+        if bit == 0 {
+            return Err(Error::InvalidOperation)
+        }
 
-        s_tag.unmanage_client(client_id);
+        let monitor_id = monitor.id;
+        let mut was_visible = self.is_client_visible(id);
+        let client = self.get_tag_mut(src)?.get_client_mut(id)?;
 
-        // Set the most recent client as input focus on the source tag if any.
-        if let Ok(c) = s_tag.get_first_client_when(|c| c.is_controlled()) {
-            s_tag.focus_client(c.id);
+        if tag_id != src {
+            client.tag_mask ^= bit;
         } else {
-            util::disable_input_focus(&conn)
+            // The client moves to its first additional tag, which then manages it.
+            let mask = client.tag_mask;
+            let monitor = self.get_monitor(monitor_id)?;
+            let dest = match monitor.tags().find(|t| mask & monitor.tag_bit(t.id) != 0) {
+                Some(t) => t.id,
+                None => return Ok(()),
+            };
+
+            let dest_bit = monitor.tag_bit(dest);
+            self.move_client(id, dest)?;
+
+            // Moving the client shows or hides it according to its new tag only.
+            was_visible = self.is_client_visible(id);
+            self.get_tag_mut(dest)?.get_client_mut(id)?.tag_mask = mask & !dest_bit;
         }
 
-        // Move the client to the destination tag
-        let d_tag = self.get_tag_mut(dest).unwrap();
+        let is_visible = self.is_client_visible(id);
+        let conn = self.conn.clone();
+        let client = self.get_tag_of_client_mut(id)?.get_client_mut(id)?;
 
-        d_tag.manage_client(client);
-        d_tag.focus_client(client_id);
-        util::set_client_tag(&conn, client_id, dest);
+        if !was_visible && is_visible {
+            client.map(&conn);
+        } else if was_visible && !is_visible {
+            client.unmap(&conn);
+            self.focus_focused_client();
+        }
 
-        _ = self.arrange_tag(dest);
-        _ = self.arrange_tag(src);
+        let focused = self.get_monitor(monitor_id)?.focused_tag_id();
+        self.arrange_tag(focused)
+    }
+
+    /// Changes the view of the monitor with ID `monitor_id` to the tags in `mask`, with the tag
+    /// with ID `focused` as its focused tag. The clients that become visible are mapped before the
+    /// clients that become hidden are unmapped.
+    fn set_view(&mut self, monitor_id: MonitorID, focused: TagID, mask: u32) -> Result<(), Error> {
+        let conn = self.conn.clone();
+        let monitor = self.get_monitor_mut(monitor_id)?;
+
+        let before = monitor.visible_clients();
+        monitor.set_view(focused, mask)?;
+        let after = monitor.visible_clients();
+
+        monitor.tags_mut()
+            .flat_map(|t| t.clients_mut())
+            .filter(|c| after.contains(&c.id) && !before.contains(&c.id))
+            .for_each(|c| c.map(&conn));
+
+        monitor.tags_mut()
+            .flat_map(|t| t.clients_mut())
+            .filter(|c| before.contains(&c.id) && !after.contains(&c.id))
+            .for_each(|c| c.unmap(&conn));
 
         Ok(())
     }
 
-    /// Refreshes the "_NET_CLIENT_LIST" with the current list of clients in all tags.
-    pub fn refresh(&self) {
-        self.get_focused_tag().unwrap().clone_clients().iter().for_each(|c| log::info!("[f] client: {}", c.id));
+    /// Detects the monitors again and updates the screen accordingly, e.g. after a monitor is
+    /// plugged, unplugged or changes its resolution. Monitors that are still connected are resized,
+    /// new ones receive their own set of tags and the clients of removed monitors are migrated to
+    /// the tag with the same index on a surviving monitor. Tag IDs are reassigned afterwards so that
+    /// the EWMH desktops remain contiguous, and all visible tags are rearranged.
+    pub fn update_monitors(&mut self) {
+        if let Ok(g) = xcb::get_geometry(&self.conn, self.root).get_reply() {
+            self.geo.width = g.width() as u32;
+            self.geo.height = g.height() as u32;
+        }
 
-        // TODO: make it less verbose and more performatic
-        let mut clients: Vec<Client> = Vec::new();
-        for t in self.tags.iter() {
-            let b = t.clone_clients();
-            for c in b.into_iter() {
-                clients.push(c);
+        let mut infos = monitor::detect(&self.conn, self.root, self.geo.width, self.geo.height);
+
+        let focused_name = self.get_focused_monitor().name.clone();
+        let aliases: Vec<String> = self.get_focused_monitor().tags().map(|t| t.alias.clone()).collect();
+
+        // Monitors that are still connected keep their tags. They are matched by the output name.
+        let mut monitors: Vec<Monitor> = Vec::new();
+        let mut removed: Vec<Monitor> = Vec::new();
+        for mut m in self.monitors.drain(..) {
+            match infos.iter().position(|i| i.name == m.name) {
+                Some(idx) => {
+                    m.set_geometry(infos.remove(idx).geo);
+                    monitors.push(m);
+                },
+                None => removed.push(m),
             }
         }
 
-        clients.iter().for_each(|c| log::info!("[s] client: {}", c.id));
-        clients.iter().rev().for_each(|c| log::info!("[s-r] client: {}", c.id));
+        // Outputs with an unknown name (e.g. the fallback monitor once RandR reports the real
+        // outputs) reuse the monitors that would be removed before new ones are created.
+        while !removed.is_empty() && !infos.is_empty() {
+            let mut m = removed.remove(0);
+            let info = infos.remove(0);
 
-        ewmh::set_client_list(
-            &self.conn,
-            0,
-            &clients.iter().map(|c| c.id).collect::<Vec<u32>>(),
-        );
+            m.name = info.name;
+            m.set_geometry(info.geo);
+            monitors.push(m);
+        }
 
-        ewmh::set_client_list_stacking(
-            &self.conn,
-            0,
-            &clients.iter().map(|c| c.id).collect::<Vec<u32>>(),
-        );
+        // The tag IDs of new monitors are reassigned below.
+        for info in infos {
+            monitors.push(Monitor::new(self.conn.clone(), 0, info, &aliases, 0));
+        }
+
+        monitors.sort_by_key(|m| (m.geo.x, m.geo.y));
+
+        let mut first_tag_id = 0;
+        for (i, m) in monitors.iter_mut().enumerate() {
+            m.id = i;
+            m.renumber_tags(first_tag_id);
+            first_tag_id += m.tags().count() as u32;
+        }
+
+        self.focused_monitor_id = monitors
+            .iter()
+            .position(|m| m.name == focused_name)
+            .unwrap_or(0);
+        self.monitors = monitors;
+
+        let conn = self.conn.clone();
+        let paddings = self.sticky_paddings(self.get_focused_monitor());
+
+        // Migrate the clients of removed monitors to the focused monitor.
+        for m in removed {
+            let target = self.get_focused_monitor_mut();
+            let target_geo = target.geo.clone();
+            let target_tag_ids: Vec<TagID> = target.tags().map(|t| t.id).collect();
+
+            for (idx, tag) in m.tags().enumerate() {
+                let dest_id = target_tag_ids[idx.min(target_tag_ids.len() - 1)];
+                let dest_visible = target.is_tag_viewed(dest_id);
+
+                let dest = target.get_tag_mut(dest_id).unwrap();
+
+                for mut c in tag.clone_clients().into_iter().rev() {
+                    let src_visible = m.is_client_visible(c.id);
+                    c.tag_mask = 0;
+
+                    Self::translate_floating(&mut c, &m.geo, &target_geo, paddings);
+
+                    let is_hidden = c.has_state(&ClientState::Hidden);
+                    if src_visible && !dest_visible {
+                        c.unmap(&conn);
+                    } else if !src_visible && dest_visible && !is_hidden {
+                        c.map(&conn);
+                    }
+
+                    dest.manage_client(c);
+                }
+            }
+        }
+
+        // As the tag IDs may have changed, the desktop of every client must be updated.
+        for t in self.tags() {
+            t.clone_clients()
+                .iter()
+                .for_each(|c| util::set_client_tag(&self.conn, c.id, t.id));
+        }
+
+        ewmh::set_number_of_desktops(&self.conn, self.id, first_tag_id);
+        ewmh::set_desktop_names(&self.conn, self.id, self.tags().map(|t| t.alias.as_ref()));
+
+        let tag_id = self.get_focused_monitor().focused_tag_id();
+        ewmh::set_current_desktop(&conn, self.id, tag_id);
+
+        self.focus_focused_client();
+
+        self.arrange_monitors();
+        self.update_workarea();
+        self.refresh();
+    }
+
+    /// Focuses the client with ID `id` wherever it is shown: on the focused tag, on another tag
+    /// viewed by the focused monitor, through its additional tags, or on the sticky tag. The tag of
+    /// the client is recorded as the owner of the focus, so that `Screen::get_focused_client()`
+    /// returns it. Clients that are not focusable are ignored. Returns
+    /// `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn focus_client(&mut self, id: ClientID) -> Result<(), Error> {
+        if self.get_focused_client().is_ok_and(|c| c.id == id) {
+            return Ok(())
+        }
+
+        let tag_id = self.get_tag_of_client(id)?.id;
+        if !self.get_tag(tag_id)?.get_client(id)?.is_focusable() {
+            return Ok(())
+        }
+
+        // Tags only update the borders of their own clients.
+        if let Ok(c) = self.get_focused_client() {
+            c.update_border(&self.conn, false);
+        }
+
+        self.get_tag_mut(tag_id)?.focus_client(id);
+
+        let focused_tag_id = self.get_focused_tag()?.id;
+        self.focus_owner = (tag_id != focused_tag_id).then_some(tag_id);
+
+        Ok(())
+    }
+
+    /// Returns the client holding the input focus: the focused client of the focused tag, unless a
+    /// client of another tag was focused more recently through `Screen::focus_client()`. Returns
+    /// `Error::ClientNotFound` when no client is focused.
+    pub fn get_focused_client(&self) -> Result<&Client, Error> {
+        self.get_tag(self.focused_client_tag_id()?)?.get_focused_client()
+    }
+
+    /// Returns the ID of the tag of the client holding the input focus. See
+    /// `Screen::get_focused_client()`.
+    pub fn focused_client_tag_id(&self) -> Result<TagID, Error> {
+        let focused = self.get_focused_tag()?;
+        let stamp = |t: &Tag| t.get_focused_client().map_or(0, |c| c.last_focused);
+
+        // Focusing a client of the focused tag afterwards (e.g. with the keyboard) takes the focus
+        // back from the owner.
+        match self.focus_owner.and_then(|id| self.get_tag(id).ok()) {
+            Some(t) if stamp(t) > stamp(focused) => Ok(t.id),
+            _ => Ok(focused.id),
+        }
+    }
+
+    /// Activates the client with ID `id`: views its tag, which may be on another monitor, and
+    /// focuses it. Any attention demanded by the client is cleared. Returns
+    /// `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn activate_client(&mut self, id: ClientID) -> Result<(), Error> {
+        let tag_id = self.get_tag_of_client(id)?.id;
+
+        // Sticky clients are always visible, so there is no tag to view.
+        if tag_id != self.sticky.id {
+            self.view_tag(tag_id)?;
+        }
+
+        self.raise_client(id);
+
+        let conn = self.conn.clone();
+        let tag = self.get_tag_mut(tag_id)?;
+
+        tag.get_client_mut(id)?.remove_state(&conn, ClientState::DemandsAttention);
+        tag.focus_client_if(id, |c| c.is_controlled());
+
+        Ok(())
+    }
+
+    /// Returns the visible clients of all tags, including the sticky tag, ordered by their focus
+    /// history, from the most recently focused.
+    pub fn focus_history(&self) -> Vec<&Client> {
+        let mut clients: Vec<&Client> = self.tags()
+            .chain(std::iter::once(&self.sticky))
+            .flat_map(|t| t.focus_history())
+            .collect();
+
+        clients.sort_by_key(|c| std::cmp::Reverse(c.last_focused));
+        clients
+    }
+
+    /// Activates the client `i` positions away in the focus history, alt-tab style. The first call
+    /// starts a cycle and grabs the keyboard, so the following calls walk from the selected client
+    /// while `modifier` is held. The cycle must be ended with `Screen::end_focus_cycle` once
+    /// `modifier` is released.
+    pub fn cycle_focus(&mut self, modifier: u16, i: i32) -> Result<(), Error> {
+        let mut cycle = match self.focus_cycle.take() {
+            Some(c) => c,
+            None => {
+                let clients = self.focus_history();
+                if clients.len() < 2 {
+                    return Ok(())
+                }
+
+                xcb::grab_keyboard(
+                    &self.conn,
+                    false,
+                    self.root,
+                    xcb::CURRENT_TIME,
+                    xcb::GRAB_MODE_ASYNC as u8,
+                    xcb::GRAB_MODE_ASYNC as u8,
+                );
+
+                FocusCycle::new(modifier, &clients)
+            },
+        };
+
+        // Clients may be closed while cycling.
+        cycle.clients.retain(|id| self.get_tag_of_client(*id).is_ok());
+
+        let idx = math::cycle_idx(cycle.clients.len(), cycle.idx as i32 + i);
+        let id = idx.map(|idx| cycle.clients[idx]);
+        cycle.idx = idx.unwrap_or(0);
+
+        self.focus_cycle = Some(cycle);
+
+        match id {
+            Some(id) => self.activate_client(id),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the modifier of the focus cycle in progress, if any.
+    pub fn focus_cycle_modifier(&self) -> Option<u16> {
+        self.focus_cycle.as_ref().map(|c| c.modifier)
+    }
+
+    /// Ends the focus cycle in progress, if any, releasing the keyboard. The selected client
+    /// becomes the most recently focused client, followed by the client that was focused when the
+    /// cycle started.
+    pub fn end_focus_cycle(&mut self) {
+        let cycle = match self.focus_cycle.take() {
+            Some(c) => c,
+            None => return,
+        };
+
+        xcb::ungrab_keyboard(&self.conn, xcb::CURRENT_TIME);
+
+        for (id, stamp) in cycle.stamps {
+            if let Ok(c) = self.get_tag_of_client_mut(id).and_then(|t| t.get_client_mut(id)) {
+                c.last_focused = stamp;
+            }
+        }
+
+        let first = cycle.clients.first().copied();
+        let selected = cycle.clients.get(cycle.idx).copied();
+
+        for id in [first, selected].into_iter().flatten() {
+            if let Ok(c) = self.get_tag_of_client_mut(id).and_then(|t| t.get_client_mut(id)) {
+                c.mark_focused();
+            }
+        }
+    }
+
+    /// Sets whether the client with ID `id` demands attention, updating its border and the urgent
+    /// tags. The focused client of the focused tag is never marked as urgent. Returns
+    /// `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn set_client_urgency(&mut self, id: ClientID, urgent: bool) -> Result<(), Error> {
+        let is_focused = self.get_focused_tag()
+            .is_ok_and(|t| t.get_focused_client().is_ok_and(|c| c.id == id));
+
+        if urgent && is_focused {
+            return Ok(())
+        }
+
+        let conn = self.conn.clone();
+        let tag = self.get_tag_of_client_mut(id)?;
+        let is_focused_on_tag = tag.get_focused_client().is_ok_and(|c| c.id == id);
+
+        let client = tag.get_client_mut(id)?;
+        client.set_state(&conn, ClientState::DemandsAttention, if urgent { Operation::Add } else { Operation::Remove })?;
+        client.update_border(&conn, is_focused_on_tag);
+
+        self.urgent_clients.retain(|c| *c != id);
+        if urgent {
+            self.urgent_clients.push(id);
+        }
+
+        self.update_urgent_tags();
+        Ok(())
+    }
+
+    /// Activates the client that most recently demanded attention, switching to its tag. Returns
+    /// `Error::ClientNotFound(0)` when no client is urgent.
+    pub fn focus_urgent_client(&mut self) -> Result<(), Error> {
+        let id = self.urgent_clients
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| {
+                self.get_tag_of_client(*id)
+                    .and_then(|t| t.get_client(*id))
+                    .is_ok_and(|c| c.is_urgent())
+            })
+            .ok_or(Error::ClientNotFound(0))?;
+
+        self.activate_client(id)?;
+        self.update_urgent_tags();
+
+        Ok(())
+    }
+
+    /// Returns the IDs of the tags that contain at least one client demanding attention.
+    pub fn urgent_tags(&self) -> Vec<TagID> {
+        self.tags()
+            .filter(|t| t.is_urgent())
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Updates the "_SAPPHIRE_URGENT_TAGS" property of the root window with the IDs of the urgent
+    /// tags, allowing bars to highlight them. Clients that are no longer urgent are forgotten.
+    pub fn update_urgent_tags(&mut self) {
+        let urgent: Vec<ClientID> = self.urgent_clients
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.get_tag_of_client(*id)
+                    .and_then(|t| t.get_client(*id))
+                    .is_ok_and(|c| c.is_urgent())
+            })
+            .collect();
+
+        self.urgent_clients = urgent;
+
+        xcb::change_property(
+            &self.conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            self.root,
+            util::get_atom(&self.conn, ipc::URGENT_TAGS_PROPERTY),
+            xcb::ATOM_CARDINAL,
+            32,
+            self.urgent_tags().as_slice(),
+        );
+    }
+
+    /// Makes the client with ID `id` sticky, showing it on every tag, or moves it back to the
+    /// focused tag. Sticky clients are floating, so they keep their geometry. Clients that are not
+    /// controlled by the window manager (e.g. docks) are always sticky. Returns
+    /// `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn set_client_sticky(&mut self, id: ClientID, sticky: bool) -> Result<(), Error> {
+        let src = self.get_tag_of_client(id)?.id;
+        let dest = if sticky { self.sticky.id } else { self.get_focused_tag()?.id };
+
+        let client = self.get_tag(src)?.get_client(id)?;
+        if src == dest || !client.is_controlled() || !client.allows_action(&ClientAction::ChangeTag) {
+            return Ok(())
+        }
+
+        let conn = self.conn.clone();
+        let src_visible = src == self.sticky.id || self.is_client_visible(id);
+
+        let s_tag = self.get_tag_mut(src)?;
+        let mut client = s_tag.get_client(id)?.clone();
+        let was_focused = s_tag.get_focused_client().is_ok_and(|c| c.id == id);
+
+        s_tag.unmanage_client(id);
+        client.tag_mask = 0;
+
+        if sticky {
+            client.add_state(&conn, ClientState::Floating);
+            client.add_state(&conn, ClientState::Sticky);
+        } else {
+            client.remove_state(&conn, ClientState::Sticky);
+        }
+
+        // Both the sticky tag and the focused tag are visible.
+        if !src_visible && !client.has_state(&ClientState::Hidden) {
+            client.map(&conn);
+        }
+
+        let d_tag = self.get_tag_mut(dest)?;
+        d_tag.manage_client(client);
+        util::set_client_tag(&conn, id, dest);
+
+        if was_focused {
+            d_tag.focus_client(id);
+        }
+
+        self.arrange_affected_by(src);
+        self.arrange_affected_by(dest);
+
+        Ok(())
+    }
+
+    /// Moves the client with ID `id` between the sticky tag and the focused tag after it became or
+    /// stopped being a dock, as docks are always managed by the sticky tag. See
+    /// `Client::classify`. Returns `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn update_client_kind(&mut self, id: ClientID) -> Result<(), Error> {
+        let src = self.get_tag_of_client(id)?.id;
+        let is_dock = self.get_tag(src)?.get_client(id)?.is_dock();
+
+        let dest = match (is_dock, src == self.sticky.id) {
+            (true, false) => self.sticky.id,
+            (false, true) => self.get_focused_tag()?.id,
+            _ => return Ok(()),
+        };
+
+        let conn = self.conn.clone();
+        let was_visible = src == self.sticky.id || self.is_client_visible(id);
+
+        let s_tag = self.get_tag_mut(src)?;
+        let mut client = s_tag.get_client(id)?.clone();
+        let was_focused = s_tag.get_focused_client().is_ok_and(|c| c.id == id);
+
+        s_tag.unmanage_client(id);
+        client.tag_mask = 0;
+
+        if was_focused {
+            if let Ok(c) = s_tag.get_previous_client() {
+                s_tag.focus_client(c.id);
+            }
+        }
+
+        // Both the sticky tag and the focused tag are visible.
+        if !was_visible && !client.has_state(&ClientState::Hidden) {
+            client.map(&conn);
+        }
+
+        self.get_tag_mut(dest)?.manage_client(client);
+        util::set_client_tag(&conn, id, dest);
+
+        self.arrange_affected_by(src);
+        self.arrange_affected_by(dest);
+
+        Ok(())
+    }
+
+    /// Minimizes (iconifies) the client with ID `id`: the client is hidden and excluded from the
+    /// layout until it is restored. When the client is focused, the focus goes to the previously
+    /// focused client of its tag. Returns `Error::ClientNotFound(id)` when no tag manages the
+    /// client.
+    pub fn minimize_client(&mut self, id: ClientID) -> Result<(), Error> {
+        let tag_id = self.get_tag_of_client(id)?.id;
+        let is_visible = self.is_client_visible(id);
+        let is_focused_tag = self.get_focused_tag().is_ok_and(|t| t.id == tag_id);
+
+        let conn = self.conn.clone();
+        let tag = self.get_tag_of_client_mut(id)?;
+        let client = tag.get_client_mut(id)?;
+
+        if !client.allows_action(&ClientAction::Minimize) || client.has_state(&ClientState::Hidden) {
+            return Ok(())
+        }
+
+        if is_visible {
+            client.unmap(&conn);
+        }
+
+        client.add_state(&conn, ClientState::Hidden);
+
+        if tag.get_focused_client().is_ok_and(|c| c.id == id) {
+            match tag.get_previous_client() {
+                Ok(c) => _ = tag.focus_client(c.id),
+                Err(_) if is_focused_tag => util::disable_input_focus(&conn),
+                Err(_) => (),
+            };
+        }
+
+        _ = self.arrange_tag(tag_id);
+        Ok(())
+    }
+
+    /// Restores the minimized client with ID `id`, activating it. Returns
+    /// `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn restore_client(&mut self, id: ClientID) -> Result<(), Error> {
+        let tag_id = self.get_tag_of_client(id)?.id;
+
+        let conn = self.conn.clone();
+        let client = self.get_tag_of_client_mut(id)?.get_client_mut(id)?;
+
+        if !client.has_state(&ClientState::Hidden) {
+            return Ok(())
+        }
+
+        client.remove_state(&conn, ClientState::Hidden);
+
+        // Clients on hidden tags are mapped when their tag is viewed.
+        if self.is_client_visible(id) {
+            self.get_tag(tag_id)?.get_client(id)?.map(&conn);
+        }
+
+        self.activate_client(id)?;
+        _ = self.arrange_tag(tag_id);
+
+        Ok(())
+    }
+
+    /// Returns the minimized clients of all tags, along with the ID of their tag. Hidden
+    /// scratchpads are not included.
+    pub fn minimized_clients(&self) -> Vec<(TagID, &Client)> {
+        self.tags()
+            .flat_map(|t| t.minimized_clients().map(move |c| (t.id, c)))
+            .filter(|(_, c)| !self.is_scratchpad(c.id))
+            .collect()
+    }
+
+    /// Verifies if the client with ID `id` belongs to a scratchpad.
+    pub fn is_scratchpad(&self, id: ClientID) -> bool {
+        self.scratchpads.iter().any(|(_, c)| *c == id)
+    }
+
+    /// Toggles the scratchpad named `name`. When it is shown on the focused tag it is hidden;
+    /// otherwise it is shown floating and centered on the focused tag and focused. When the
+    /// scratchpad has no client, its command is spawned and the client is attached once mapped;
+    /// see `Screen::attach_scratchpad`. Returns `Error::ScratchpadNotFound(name)` when no
+    /// scratchpad is configured with the name.
+    pub fn toggle_scratchpad(&mut self, name: &str) -> Result<(), Error> {
+        let config = Config::current();
+        let pad = config.scratchpads
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or(Error::ScratchpadNotFound(name.to_owned()))?;
+
+        // Forget the client if it is no longer managed, e.g. the user closed it.
+        let managed: Vec<(String, ClientID)> = self.scratchpads
+            .iter()
+            .filter(|(_, c)| self.get_tag_of_client(*c).is_ok())
+            .cloned()
+            .collect();
+        self.scratchpads = managed;
+
+        let id = match self.scratchpads.iter().find(|(n, _)| n == name) {
+            Some((_, id)) => *id,
+            None => return util::spawn(&pad.command),
+        };
+
+        let focused_tag_id = self.get_focused_tag()?.id;
+        let tag_id = self.get_tag_of_client(id)?.id;
+        let is_hidden = self.get_tag(tag_id)?.get_client(id)?.has_state(&ClientState::Hidden);
+
+        if !is_hidden && tag_id == focused_tag_id {
+            return self.minimize_client(id)
+        }
+
+        let geo = self.get_focused_monitor().geo.clone();
+        let conn = self.conn.clone();
+        let client = self.get_tag_of_client_mut(id)?.get_client_mut(id)?;
+        Self::float_centered(&conn, client, &geo);
+
+        self.move_client(id, focused_tag_id)?;
+
+        if is_hidden {
+            self.restore_client(id)
+        } else {
+            self.activate_client(id)
+        }
+    }
+
+    /// Attaches `client` to the first scratchpad without client whose rules match, making it
+    /// floating and centered on the focused monitor. Must be called before the client is managed.
+    /// Returns `true` when the client belongs to a scratchpad.
+    pub fn attach_scratchpad(&mut self, client: &mut Client) -> bool {
+        let config = Config::current();
+        let pad = config.scratchpads
+            .iter()
+            .filter(|p| !self.scratchpads.iter().any(|(n, c)| *n == p.name && self.get_tag_of_client(*c).is_ok()))
+            .find(|p| p.matches(client));
+
+        let pad = match pad {
+            Some(p) => p,
+            None => return false,
+        };
+
+        log::info!("client attached to scratchpad. client={} scratchpad={}", client.id, pad.name);
+
+        self.scratchpads.retain(|(n, _)| *n != pad.name);
+        self.scratchpads.push((pad.name.clone(), client.id));
+
+        let geo = self.get_focused_monitor().geo.clone();
+        Self::float_centered(&self.conn, client, &geo);
+
+        true
+    }
+
+    /// Makes `client` floating, resizing it to 60% of the monitor geometry `geo` (within its size
+    /// hints) and centering it on the monitor.
+    fn float_centered(conn: &ewmh::Connection, client: &mut Client, geo: &MonitorGeometry) {
+        let border = Config::current().border.width;
+        let (w, h) = client.geo.clamp_size(geo.w * 3 / 5, geo.h * 3 / 5);
+
+        client.geo.border = border;
+        client.geo.w = w;
+        client.geo.h = h;
+        client.geo.x = geo.x + geo.w.saturating_sub(w + border * 2) / 2;
+        client.geo.y = geo.y + geo.h.saturating_sub(h + border * 2) / 2;
+
+        client.add_state(conn, ClientState::Floating);
+    }
+
+    /// Moves the currently focused client from the source tag to destination tag, where it becomes
+    /// the focused client. Returns `Error::TagNotFound(src|dest)` when any provided ID does not
+    /// exist.
+    pub fn move_focused_client(&mut self, src: TagID, dest: TagID) -> Result<(), Error> {
+        let client_id = self.get_tag(src)?.get_focused_client()?.id;
+        self.move_client(client_id, dest)?;
+
+        self.get_tag_mut(dest)?.focus_client(client_id);
+
+        // The destination tag may be hidden, so the input focus must be given back to the focused
+        // tag.
+        if !self.is_tag_visible(dest) {
+            self.focus_focused_client();
+        }
+
+        Ok(())
+    }
+
+    /// Moves the client with ID `id` from its current tag to the destination tag, showing or hiding
+    /// it according to the visibility of both tags. Returns `Error::TagNotFound(dest)` when the
+    /// destination tag does not exist and `Error::ClientNotFound(id)` when no tag manages the client.
+    pub fn move_client(&mut self, id: ClientID, dest: TagID) -> Result<(), Error> {
+        if !self.contains_tag(dest) {
+            return Err(Error::TagNotFound(dest))
+        }
+
+        let src = self.get_tag_of_client(id)?.id;
+
+        // Sticky clients are shown in all tags.
+        if src == dest || src == self.sticky.id || dest == self.sticky.id {
+            return Ok(())
+        }
+
+        let conn = self.conn.clone();
+        let src_visible = self.is_tag_visible(src);
+        let dest_visible = self.is_tag_visible(dest);
+        let was_visible = self.is_client_visible(id);
+
+        // Unmanage the client from the source tag.
+        let s_tag = self.get_tag_mut(src)?;
+
+        let mut client = s_tag.get_client(id)?.clone();
+        let was_focused = s_tag.get_focused_client().is_ok_and(|c| c.id == id);
+
+        s_tag.unmanage_client(id);
+
+        // The client is only shown on the destination tag.
+        client.tag_mask = 0;
+
+        // Set the previously focused client as input focus on the source tag if any.
+        if was_focused {
+            if let Ok(c) = s_tag.get_previous_client() {
+                s_tag.focus_client(c.id);
+            } else if src_visible {
+                util::disable_input_focus(&conn)
+            }
+        }
+
+        // Floating clients keep their position relative to the monitor.
+        let src_geo = self.get_monitor_of_tag(src)?.geo.clone();
+        let dest_monitor = self.get_monitor_of_tag(dest)?;
+        if src_geo != dest_monitor.geo {
+            let paddings = self.sticky_paddings(dest_monitor);
+            Self::translate_floating(&mut client, &src_geo, &dest_monitor.geo, paddings);
+        }
+
+        // Minimized clients remain unmapped.
+        let is_hidden = client.has_state(&ClientState::Hidden);
+        if was_visible && !dest_visible {
+            client.unmap(&conn);
+        } else if !was_visible && dest_visible && !is_hidden {
+            client.map(&conn);
+        }
+
+        // Move the client to the destination tag.
+        self.get_tag_mut(dest)?.manage_client(client);
+        util::set_client_tag(&conn, id, dest);
+
+        _ = self.arrange_tag(dest);
+        _ = self.arrange_tag(src);
+
+        Ok(())
+    }
+
+    /// Stops managing the client with ID `id`, whichever tag it is on. When the client was focused
+    /// on the focused tag, the focus goes to the client it swallowed or to the previously focused
+    /// client of the tag, if any. The tag
+    /// is rearranged; for sticky clients, which may reserve space on every monitor, all monitors
+    /// are. Returns `Error::ClientNotFound(id)` when no tag manages the client.
+    /// Note: It does not update the "_NET_CLIENT_LIST"; use `Screen::refresh()` for that purpose.
+    pub fn unmanage_client(&mut self, id: ClientID) -> Result<(), Error> {
+        // Swallowed clients are not managed by any tag, so they are only forgotten.
+        if self.monitors.iter_mut().flat_map(|m| m.tags_mut()).any(|t| t.forget_swallowed(id)) {
+            return Ok(())
+        }
+
+        let tag_id = self.get_tag_of_client(id)?.id;
+        let is_focused_tag = self.get_focused_tag().is_ok_and(|t| t.id == tag_id);
+        let is_visible = self.is_tag_visible(tag_id);
+
+        let conn = self.conn.clone();
+        let tag = self.get_tag_mut(tag_id)?;
+
+        let was_focused = tag.get_focused_client().is_ok_and(|c| c.id == id);
+
+        // The client swallowed by the client, if any, gets back its position.
+        let restored = match tag.unswallow_client(id) {
+            Some(c) => {
+                if is_visible {
+                    c.map(&conn);
+                }
+
+                Some(c.id)
+            },
+            None => {
+                tag.unmanage_client(id);
+                None
+            },
+        };
+
+        // Focus the restored client or the previously focused client if any; otherwise, disable
+        // the focus.
+        if was_focused && is_focused_tag {
+            let next = restored.or_else(|| tag.get_previous_client().ok().map(|c| c.id));
+
+            match next {
+                Some(id) => _ = tag.focus_client(id),
+                None => util::disable_input_focus(&conn),
+            };
+        }
+
+        // A client holding the focus outside of the focused tag (e.g. a sticky client) gives the
+        // focus back to the focused tag.
+        if was_focused && !is_focused_tag && self.focus_owner == Some(tag_id) {
+            self.focus_focused_client();
+        }
+
+        self.arrange_affected_by(tag_id);
+
+        Ok(())
+    }
+
+    /// Verifies if the tag with ID `id` is visible on any monitor.
+    pub fn is_tag_visible(&self, id: TagID) -> bool {
+        self.monitors.iter().any(|m| m.is_tag_viewed(id))
+    }
+
+    /// Verifies if the client with ID `id` is shown, either by the tag that manages it or by one
+    /// of its additional tags (see `Client::tag_mask`). Sticky clients are always shown, unless
+    /// minimized.
+    pub fn is_client_visible(&self, id: ClientID) -> bool {
+        match self.sticky.get_client(id) {
+            Ok(c) => !c.has_state(&ClientState::Hidden),
+            Err(_) => self.monitors.iter().any(|m| m.is_client_visible(id)),
+        }
+    }
+
+    /// Sets the input focus to the focused client of the focused tag, if any; otherwise disables
+    /// the input focus.
+    fn focus_focused_client(&mut self) {
+        let conn = self.conn.clone();
+        self.focus_owner = None;
+
+        match self.get_focused_tag_mut().and_then(|t| t.get_focused_client_mut()) {
+            Ok(c) => c.set_input_focus(&conn),
+            Err(_) => util::disable_input_focus(&conn),
+        }
+    }
+
+    /// Refreshes the "_NET_CLIENT_LIST" with the current list of clients in all tags, as well as
+    /// the urgent tags.
+    pub fn refresh(&mut self) {
+        self.update_urgent_tags();
+
+        self.get_focused_tag().unwrap().clone_clients().iter().for_each(|c| log::info!("[f] client: {}", c.id));
+
+        // TODO: make it less verbose and more performatic
+        let mut clients: Vec<Client> = Vec::new();
+        for t in self.tags().chain(std::iter::once(&self.sticky)) {
+            let b = t.clone_clients();
+            for c in b.into_iter() {
+                clients.push(c);
+            }
+        }
+
+        clients.iter().for_each(|c| log::info!("[s] client: {}", c.id));
+        clients.iter().rev().for_each(|c| log::info!("[s-r] client: {}", c.id));
+
+        ewmh::set_client_list(
+            &self.conn,
+            0,
+            &clients.iter().map(|c| c.id).collect::<Vec<u32>>(),
+        );
+
+        self.restack();
+    }
+
+    /// Moves the client with ID `id` to the top of its stacking layer. It only takes effect on the
+    /// next `Screen::restack`.
+    pub fn raise_client(&mut self, id: ClientID) {
+        self.stack.retain(|c| *c != id);
+        self.stack.push(id);
+    }
+
+    /// Restacks the client with ID `id` relative to the client `sibling` within its stacking layer,
+    /// as requested by "_NET_RESTACK_WINDOW". `mode` is one of the `xcb::STACK_MODE_*` values.
+    /// Without a valid sibling, `STACK_MODE_ABOVE` raises the client and `STACK_MODE_BELOW` lowers
+    /// it. As SapphireWM does not track occlusion, `STACK_MODE_TOP_IF` and `STACK_MODE_OPPOSITE`
+    /// raise the client and `STACK_MODE_BOTTOM_IF` lowers it. Returns `Error::ClientNotFound(id)`
+    /// when no tag manages the client.
+    pub fn restack_client(&mut self, id: ClientID, sibling: ClientID, mode: u32) -> Result<(), Error> {
+        self.get_tag_of_client(id)?;
+        self.sync_stack();
+
+        self.stack.retain(|c| *c != id);
+        let sibling_idx = self.stack.iter().position(|c| *c == sibling);
+
+        let idx = match (mode, sibling_idx) {
+            (xcb::STACK_MODE_ABOVE, Some(i)) => i + 1,
+            (xcb::STACK_MODE_BELOW, Some(i)) => i,
+            (xcb::STACK_MODE_BELOW | xcb::STACK_MODE_BOTTOM_IF, _) => 0,
+            _ => self.stack.len(),
+        };
+
+        self.stack.insert(idx, id);
+        self.restack();
+
+        Ok(())
+    }
+
+    /// Synchronizes the stack with the managed clients. Unmanaged clients are removed and new
+    /// clients are placed at the top.
+    fn sync_stack(&mut self) {
+        let managed: Vec<ClientID> = self.tags()
+            .chain(std::iter::once(&self.sticky))
+            .flat_map(|t| t.clone_clients())
+            .map(|c| c.id)
+            .collect();
+
+        self.stack.retain(|id| managed.contains(id));
+
+        // The clients of a tag are ordered from the newest to the oldest.
+        for id in managed.into_iter().rev() {
+            if !self.stack.contains(&id) {
+                self.stack.push(id);
+            }
+        }
+    }
+
+    /// Restacks all clients according to their stacking layers and updates the
+    /// "_NET_CLIENT_LIST_STACKING" with the resulting bottom-to-top order.
+    pub fn restack(&mut self) {
+        self.sync_stack();
+
+        let layers: Vec<(ClientID, StackLayer)> = self.tags()
+            .chain(std::iter::once(&self.sticky))
+            .flat_map(|t| t.clone_clients())
+            .map(|c| (c.id, c.layer()))
+            .collect();
+
+        let layer_of = |id: &ClientID| {
+            layers
+                .iter()
+                .find(|(c, _)| c == id)
+                .map_or(StackLayer::Normal, |(_, l)| *l)
+        };
+
+        // The sort is stable, so the order within each layer is kept.
+        let mut order = self.stack.clone();
+        order.sort_by_key(layer_of);
+
+        // The bottommost client is lowered and each other client is stacked right above the
+        // previous one.
+        for (i, id) in order.iter().enumerate() {
+            match i {
+                0 => xcb::configure_window(
+                    &self.conn,
+                    *id,
+                    &[(xcb::CONFIG_WINDOW_STACK_MODE as u16, xcb::STACK_MODE_BELOW)],
+                ),
+                _ => xcb::configure_window(
+                    &self.conn,
+                    *id,
+                    &[
+                        (xcb::CONFIG_WINDOW_SIBLING as u16, order[i - 1]),
+                        (xcb::CONFIG_WINDOW_STACK_MODE as u16, xcb::STACK_MODE_ABOVE),
+                    ],
+                ),
+            };
+        }
+
+        ewmh::set_client_list_stacking(&self.conn, 0, &order);
     }
 }
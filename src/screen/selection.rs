@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use xcb_util::ewmh;
+
+use crate::{
+    errors::Error,
+    util,
+};
+
+/// How long to wait for the previous window manager to exit when replacing it.
+const REPLACE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Acquires the ICCCM manager selection `WM_S<screen_id>` with the window `owner`. When another
+/// window manager owns the selection, it is only replaced if `replace` is `true`, in which case
+/// SapphireWM waits for the previous owner to release the screen. Once acquired, the other clients
+/// are notified through a `MANAGER` client message.
+///
+/// Returns the atom of the selection, or an error when the selection is owned by another window
+/// manager that could not be replaced.
+/// See: https://tronche.com/gui/x/icccm/sec-2.html#s-2.8
+pub fn acquire(conn: &ewmh::Connection, screen_id: i32, root: u32, owner: u32, replace: bool) -> Result<xcb::Atom, Error> {
+    let selection = util::get_atom(conn, &format!("WM_S{}", screen_id));
+
+    let old_owner = xcb::get_selection_owner(conn, selection)
+        .get_reply()
+        .map_or(xcb::NONE, |r| r.owner());
+
+    if old_owner != xcb::NONE {
+        if !replace {
+            return Err(Error::Custom("Another window manager is already running. Use --replace to replace it.".to_owned()))
+        }
+
+        // Get notified when the previous owner is destroyed.
+        xcb::change_window_attributes(
+            conn,
+            old_owner,
+            &[(xcb::CW_EVENT_MASK, xcb::EVENT_MASK_STRUCTURE_NOTIFY)],
+        );
+    }
+
+    // The ICCCM does not allow using `CURRENT_TIME` to acquire the selection.
+    let time = get_timestamp(conn, owner);
+
+    xcb::set_selection_owner(conn, owner, selection, time);
+
+    if !xcb::get_selection_owner(conn, selection).get_reply().is_ok_and(|r| r.owner() == owner) {
+        return Err(Error::Custom("Unable to acquire the window manager selection.".to_owned()))
+    }
+
+    if old_owner != xcb::NONE && !wait_for_destroy(conn, old_owner) {
+        return Err(Error::Custom("The running window manager did not exit in time.".to_owned()))
+    }
+
+    let event = xcb::ClientMessageEvent::new(
+        32,
+        root,
+        conn.MANAGER(),
+        xcb::ClientMessageData::from_data32([
+            time,
+            selection,
+            owner,
+            xcb::NONE,
+            xcb::NONE,
+        ]),
+    );
+
+    xcb::send_event(conn, false, root, xcb::EVENT_MASK_STRUCTURE_NOTIFY, &event);
+    conn.flush();
+
+    Ok(selection)
+}
+
+/// Retrieves the current server time by appending zero-length data to a property of `window`, as
+/// suggested by the ICCCM.
+fn get_timestamp(conn: &ewmh::Connection, window: u32) -> xcb::Timestamp {
+    xcb::change_window_attributes(
+        conn,
+        window,
+        &[(xcb::CW_EVENT_MASK, xcb::EVENT_MASK_PROPERTY_CHANGE)],
+    );
+
+    xcb::change_property(
+        conn,
+        xcb::PROP_MODE_APPEND as u8,
+        window,
+        conn.WM_NAME(),
+        xcb::ATOM_STRING,
+        8,
+        &[] as &[u8],
+    );
+
+    conn.flush();
+
+    while let Some(e) = conn.wait_for_event() {
+        if e.response_type() & !0x80 == xcb::PROPERTY_NOTIFY {
+            let e: &xcb::PropertyNotifyEvent = unsafe { xcb::cast_event(&e) };
+            if e.window() == window {
+                return e.time()
+            }
+        }
+    }
+
+    xcb::CURRENT_TIME
+}
+
+/// Waits until `window` is destroyed. Returns `false` when the window still exists after
+/// `REPLACE_TIMEOUT`.
+fn wait_for_destroy(conn: &ewmh::Connection, window: u32) -> bool {
+    conn.flush();
+
+    let start = Instant::now();
+
+    while start.elapsed() < REPLACE_TIMEOUT {
+        match conn.poll_for_event() {
+            Some(e) if e.response_type() & !0x80 == xcb::DESTROY_NOTIFY => {
+                let e: &xcb::DestroyNotifyEvent = unsafe { xcb::cast_event(&e) };
+                if e.window() == window {
+                    return true
+                }
+            },
+            Some(_) => (),
+            None => std::thread::sleep(Duration::from_millis(50)),
+        }
+    }
+
+    false
+}
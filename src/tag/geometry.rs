@@ -1,7 +1,8 @@
 /// Represents the geometry of a tag, which is used for calculating coordinates for a drawable
 /// client.
 ///
-/// the `w` and `h` fields represent the total width and height of the tag's screen respsectively.
+/// the `x` and `y` fields represent the position of the tag's monitor relative to the root window,
+/// while the `w` and `h` fields represent the total width and height of the tag's monitor respsectively.
 /// additionaly `avail_w` and `avail_h` provide the available width and height for content,
 /// factoring in the padding. The `paddings` array consists of four elements, where each index
 /// represents a specific padding:
@@ -19,6 +20,12 @@
 /// width and height are usually reserved for drawing fullscreen clients.
 #[derive(Clone)]
 pub struct TagGeometry {
+    /// Horizontal position of the tag, relative to the root window.
+    pub x: u32,
+
+    /// Vertical position of the tag, relative to the root window.
+    pub y: u32,
+
     /// Total width of the tag.
     pub w: u32,
 
@@ -45,9 +52,11 @@ pub struct TagGeometry {
 }
 
 impl TagGeometry {
-    /// Creates a new `TagGeometry` instance with the given dimensions and paddings.
-    pub fn new(w: u32, h: u32, paddings: [u32; 4]) -> Self {
+    /// Creates a new `TagGeometry` instance with the given position, dimensions and paddings.
+    pub fn new(x: u32, y: u32, w: u32, h: u32, paddings: [u32; 4]) -> Self {
         Self {
+            x,
+            y,
             w,
             h,
             paddings,
@@ -56,6 +65,17 @@ impl TagGeometry {
         }
     }
 
+    /// Moves and resizes the tag to the given position and dimensions, recomputing the available
+    /// width and height with the current paddings.
+    pub fn resize(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        self.x = x;
+        self.y = y;
+        self.w = w;
+        self.h = h;
+        self.avail_w = w.saturating_sub(self.paddings[2] + self.paddings[3]);
+        self.avail_h = h.saturating_sub(self.paddings[0] + self.paddings[1]);
+    }
+
     /// Returns the top padding of the tag.
     #[inline(always)]
     pub fn padding_top(&self) -> u32 {
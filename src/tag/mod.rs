@@ -18,6 +18,17 @@ pub use crate::tag::geometry::TagGeometry;
 
 pub type TagID = u32;
 
+/// Represents the kind of a tag.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TagKind {
+    /// A tag shown on a single monitor at a time, whose clients are arranged by the layout.
+    Normal,
+
+    /// The tag whose clients are shown on every tag of every monitor, such as docks and sticky
+    /// (e.g. picture-in-picture) windows. Its clients are never arranged by the layout.
+    Sticky,
+}
+
 #[derive(Clone)]
 pub struct Tag {
     /// EWMH | XCB connection.
@@ -30,38 +41,43 @@ pub struct Tag {
     /// The name of the tag, used to define "_NET_DESKTOP_NAMES".
     pub alias: String,
 
+    pub kind: TagKind,
+
     pub geo: TagGeometry,
 
     /// ID of the currently focused client. It is 0 when no client is focused.
     focused_cid: ClientID,
 
+    /// ID of the client that was the master before the last `Tag::zoom`. It is 0 when there is no
+    /// previous master.
+    prev_master_cid: ClientID,
+
     clients: VecDeque<Client>,
 }
 
 impl Tag {
-    pub fn new(conn: Arc<ewmh::Connection>, id: u32, alias: &str, width: u32, height: u32) -> Self {
+    pub fn new(conn: Arc<ewmh::Connection>, id: u32, alias: &str, kind: TagKind, geo: TagGeometry) -> Self {
         // TODO: better message
         log::trace!(
-            "creating tag. id={} alias={} geo={{width={} height={}}}",
+            "creating tag. id={} alias={} kind={:?} geo={{x={} y={} width={} height={}}}",
             id,
             alias,
-            width,
-            height,
+            kind,
+            geo.x,
+            geo.y,
+            geo.w,
+            geo.h,
         );
 
         Self {
             id,
             conn,
             alias: alias.to_owned(),
+            kind,
             focused_cid: 0,
+            prev_master_cid: 0,
             clients: VecDeque::new(),
-            geo: TagGeometry {
-                w: width, 
-                avail_w: width,
-                h: height, 
-                avail_h: height,
-                paddings: [0, 0, 0, 0],
-            },
+            geo,
         }
     }
 
@@ -82,8 +98,8 @@ impl Tag {
         self.geo.paddings[2] = self.geo.paddings[2].max(left);
         self.geo.paddings[3] = self.geo.paddings[3].max(right);
 
-        // TODO: remove this!
-        if self.alias != "sticky_clients" {
+        // The sticky tag is shared by all monitors, so its paddings are computed per monitor.
+        if self.kind != TagKind::Sticky {
             self.geo.avail_w =  self.geo.w - left - right;
             self.geo.avail_h = self.geo.h - top - bottom;
         }
@@ -102,11 +118,68 @@ impl Tag {
         self.clients.push_front(client);
     }
 
-    /// Removes a client with the specified window ID from the client list.
+    /// Removes a client with the specified window ID from the client list. When the client is the
+    /// focused one, the tag is left without a focused client.
     /// Note: It does not update the "_NET_CLIENT_LIST"; use `Screen::refresh()` for that purpose.
     pub fn unmanage_client(&mut self, wid: ClientID) {
         self.clients.retain(|c| c.id != wid);
 
+        if self.focused_cid == wid {
+            self.focused_cid = 0;
+        }
+
+        self.update_paddings();
+    }
+
+    /// Replaces the client with ID `id` by `client`, which takes its position in the layout and
+    /// swallows it. Returns `Error::ClientNotFound(id)` when the tag does not manage the client.
+    /// Note: It does not update the "_NET_CLIENT_LIST"; use `Screen::refresh()` for that purpose.
+    pub fn swallow_client(&mut self, id: ClientID, mut client: Client) -> Result<(), Error> {
+        let idx = self.get_client_idx(id).ok_or(Error::ClientNotFound(id))?;
+        let swallowed = self.clients.remove(idx).ok_or(Error::ClientNotFound(id))?;
+
+        client.swallow(&self.conn, swallowed);
+        self.clients.insert(idx, client);
+
+        if self.focused_cid == id {
+            self.focused_cid = 0;
+        }
+
+        self.update_paddings();
+
+        Ok(())
+    }
+
+    /// Replaces the client with ID `id` by the client it swallowed, which gets back its position
+    /// in the layout. Returns the restored client, which is not mapped, or `None` when the client
+    /// does not exist or has not swallowed any client.
+    /// Note: It does not update the "_NET_CLIENT_LIST"; use `Screen::refresh()` for that purpose.
+    pub fn unswallow_client(&mut self, id: ClientID) -> Option<&Client> {
+        let idx = self.get_client_idx(id)?;
+        let swallowed = self.clients[idx].unswallow()?;
+
+        self.clients[idx] = swallowed;
+
+        if self.focused_cid == id {
+            self.focused_cid = 0;
+        }
+
+        self.update_paddings();
+
+        self.clients.get(idx)
+    }
+
+    /// Drops the client with ID `id` from the clients swallowed by the clients of the tag. Returns
+    /// `true` when the client was found.
+    pub fn forget_swallowed(&mut self, id: ClientID) -> bool {
+        self.clients.iter_mut().any(|c| c.forget_swallowed(id))
+    }
+
+    /// Recomputes the paddings of the tag from the paddings of its clients. Must be called when
+    /// the struts of a client change.
+    pub fn update_paddings(&mut self) {
+        self.geo.paddings = [0, 0, 0, 0];
+
         self.set_paddings(
             self.clients.iter().map(|c| c.geo.paddings[0]).max().unwrap_or(0),
             self.clients.iter().map(|c| c.geo.paddings[1]).max().unwrap_or(0),
@@ -123,15 +196,36 @@ impl Tag {
             .ok_or(Error::ClientNotFound(0))
     }
 
+    /// Returns the visible clients of the tag ordered by their focus history, from the most
+    /// recently focused. Clients that were never focused come last, in the list order.
+    pub fn focus_history(&self) -> Vec<&Client> {
+        let mut clients: Vec<&Client> = self.clients
+            .iter()
+            .filter(|c| c.is_focusable())
+            .collect();
+
+        clients.sort_by_key(|c| std::cmp::Reverse(c.last_focused));
+        clients
+    }
+
+    /// Retrieves an immutable reference to the most recently focused visible client, other than
+    /// the focused one. Falls back to the master (first) client when no other client was focused.
+    pub fn get_previous_client(&self) -> Result<&Client, Error> {
+        self.focus_history()
+            .into_iter()
+            .find(|c| c.id != self.focused_cid)
+            .ok_or(Error::ClientNotFound(0))
+    }
+
     /// Retrieves an immutable reference to the client with the specified ID.
-    // pub fn get_client(&self, id: ClientID) -> Result<&Client, Error> {
-    //     self.clients
-    //         .iter()
-    //         .find(|c| c.id == id)
-    //         .ok_or(Error::ClientNotFound(id))
-    // }
+    pub fn get_client(&self, id: ClientID) -> Result<&Client, Error> {
+        self.clients
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or(Error::ClientNotFound(id))
+    }
     
-    /// Retrieves an immutable reference to the client with the specified ID.
+    /// Retrieves a mutable reference to the client with the specified ID.
     pub fn get_client_mut(&mut self, id: ClientID) -> Result<&mut Client, Error> {
         self.clients
             .iter_mut()
@@ -171,7 +265,7 @@ impl Tag {
 
         match clients.len() {
             0 => return None,
-            1 => return Some(&self.clients[0]),
+            1 => return Some(clients[0]),
             _ => (),
         };
 
@@ -179,11 +273,11 @@ impl Tag {
         let relative = relative.unwrap_or(self.focused_cid);
         let relative_idx = clients.iter().position(|c| c.id == relative)? as i32;
 
+        // The target index is relative to the visible clients.
         let target = math::cycle_idx(clients.len(), relative_idx + i)?;
-        self.clients.get(target)
+        clients.get(target).copied()
     }
 
-
     /// Sets focus on a client with the specified window ID, updating the border to `active_color`
     /// and setting the client as the input focus. If there's another focused client, updates its
     /// border to `inactive_color`.
@@ -199,27 +293,43 @@ impl Tag {
     where
         P: Fn(&Client) -> bool
     {
-        if let Some(c) = self.clients.iter().find(|c| c.id == wid) {
-            if !predicate(c) {
-                return Some(false)
-            }
+        let idx = self.get_client_idx(wid)?;
+        if !predicate(&self.clients[idx]) {
+            return Some(false)
+        }
 
-            let config = Config::current();
+        // Sets the border of the previously focused client to an inactive state, if applicable.
+        self.clients
+            .iter()
+            .find(|c| c.id == self.focused_cid)
+            .map(|c| c.update_border(&self.conn, false));
 
-            // Sets the border of the previously focused client to an inactive state, if applicable.
-            self.clients
-                .iter()
-                .find(|c| c.id == self.focused_cid)
-                .map(|c| c.set_border(&self.conn, config.border.color_normal));
-            
-            self.focused_cid = c.id;
-            c.set_input_focus(&self.conn); // TODO: make this a tag method
-            c.set_border(&self.conn, config.border.color_active);
+        // Focusing a client answers its demand for attention.
+        let c = &mut self.clients[idx];
+        c.remove_state(&self.conn, ClientState::DemandsAttention);
 
-            return Some(true)
+        self.focused_cid = c.id;
+        c.mark_focused();
+        c.set_input_focus(&self.conn); // TODO: make this a tag method
+        c.update_border(&self.conn, true);
+
+        Some(true)
+    }
+
+    /// Replaces the client with ID `id` by `client`, which takes its position in the layout and, if
+    /// the replaced client was focused, its focus. Returns the replaced client or
+    /// `Error::ClientNotFound(id)` when the client does not exist.
+    pub fn replace_client(&mut self, id: ClientID, client: Client) -> Result<Client, Error> {
+        let idx = self.get_client_idx(id).ok_or(Error::ClientNotFound(id))?;
+
+        if self.focused_cid == id {
+            self.focused_cid = client.id;
         }
 
-        None
+        let replaced = std::mem::replace(&mut self.clients[idx], client);
+        self.update_paddings();
+
+        Ok(replaced)
     }
 
     /// Sets focus on a client by its relative index to another client's ID. updating the border to
@@ -236,26 +346,41 @@ impl Tag {
         Ok(())
     }
 
+    /// Exchanges the clients, and thus the focused client and paddings, of the tag with the
+    /// clients of the tag `other`. The geometry of both tags is kept.
+    pub fn swap_clients(&mut self, other: &mut Tag) {
+        std::mem::swap(&mut self.clients, &mut other.clients);
+        std::mem::swap(&mut self.focused_cid, &mut other.focused_cid);
+        std::mem::swap(&mut self.prev_master_cid, &mut other.prev_master_cid);
+        std::mem::swap(&mut self.geo.paddings, &mut other.geo.paddings);
+
+        self.geo.resize(self.geo.x, self.geo.y, self.geo.w, self.geo.h);
+        other.geo.resize(other.geo.x, other.geo.y, other.geo.w, other.geo.h);
+    }
+
+    /// Returns an iterator over the minimized (hidden) clients of the tag, from the most recently
+    /// managed to the oldest.
+    pub fn minimized_clients(&self) -> impl Iterator<Item = &Client> {
+        self.clients.iter().filter(|c| c.has_state(&ClientState::Hidden))
+    }
+
+    /// Verifies if any client of the tag demands attention.
+    pub fn is_urgent(&self) -> bool {
+        self.clients.iter().any(|c| c.is_urgent())
+    }
+
     pub fn clone_clients(&self) -> Vec<Client> {
         self.clients.iter().cloned().collect()
     }
     
-    /// Maps all visible clients of the tag.
-    pub fn map(&self) {
-        self.clients
-            .iter()
-            .for_each(|c| {
-                if !c.has_state(&ClientState::Hidden) { c.map(&self.conn) }
-            });
+    /// Returns an iterator over the clients of the tag.
+    pub fn clients(&self) -> impl Iterator<Item = &Client> {
+        self.clients.iter()
     }
 
-    /// Unmaps all visible clients of the tag.
-    pub fn unmap(&self) {
-        self.clients
-            .iter()
-            .for_each(|c| {
-                if !c.has_state(&ClientState::Hidden) { c.unmap(&self.conn) }
-            });
+    /// Returns a mutable iterator over the clients of the tag.
+    pub fn clients_mut(&mut self) -> impl Iterator<Item = &mut Client> {
+        self.clients.iter_mut()
     }
 
     /// Changes the position of the client with window ID `wid_i` with the client with window ID `wid_j`.
@@ -267,49 +392,117 @@ impl Tag {
         }
     }
 
-    pub fn arrange<T>(&mut self, layout: &T, sticky: &Tag)
+    /// Moves the client with ID `id` by `i` positions among the visible clients, exchanging it with
+    /// the client at the target position. Walking beyond the last client loops back to the first
+    /// one. Returns `None` if the client does not exist.
+    pub fn move_client_byidx(&mut self, id: ClientID, i: i32) -> Option<()> {
+        let other = self.get_client_byidx(i, Some(id))?.id;
+        self.swap(id, other)
+    }
+
+    /// Rotates the clients of the tag by `n` positions. A positive `n` moves every client towards
+    /// the end of the list, so the last clients become the first ones; a negative `n` rotates in
+    /// the opposite direction. The focused client is kept.
+    pub fn rotate(&mut self, n: i32) {
+        if self.clients.is_empty() {
+            return
+        }
+
+        let n = n.rem_euclid(self.clients.len() as i32) as usize;
+        self.clients.rotate_right(n);
+    }
+
+    /// Moves the focused client to the master (first) position of the layout. When it is already
+    /// the master, the previous master takes its place back, falling back to the next tiled client.
+    /// The moved client is focused. Returns `None` when the focused client is not tiled or there is
+    /// no client to move.
+    pub fn zoom(&mut self) -> Option<()> {
+        let is_tiled = |c: &Client| c.is_focusable() && c.get_state() == ClientState::Tile;
+
+        let focused = self.get_focused_client().ok()?;
+        if !is_tiled(focused) {
+            return None
+        }
+
+        let master = self.get_first_client_when(is_tiled).ok()?.id;
+        let target = if focused.id != master {
+            focused.id
+        } else {
+            match self.get_client(self.prev_master_cid) {
+                Ok(c) if is_tiled(c) => c.id,
+                _ => self.clients.iter().filter(|c| is_tiled(c)).nth(1)?.id,
+            }
+        };
+
+        let idx = self.get_client_idx(target)?;
+        let client = self.clients.remove(idx)?;
+        self.clients.push_front(client);
+
+        self.prev_master_cid = master;
+        self.focus_client(target);
+
+        Some(())
+    }
+
+    /// Readjusts the layout of the tag. `paddings` are the paddings reserved by the sticky clients
+    /// (e.g. docks) on the tag's monitor and are merged with the paddings of the tag.
+    pub fn arrange<T>(&mut self, layout: &T, paddings: [u32; 4])
+    where
+        T: Layout
+    {
+        self.arrange_with(layout, paddings, vec![]);
+    }
+
+    /// Same as `Tag::arrange()` but also arranges `others`, clients of other tags shown together
+    /// with the tag (see `Monitor::arrange`), after the clients of the tag.
+    pub fn arrange_with<T>(&mut self, layout: &T, paddings: [u32; 4], others: Vec<&mut Client>)
     where
         T: Layout
     {
+        let mut clients: Vec<&mut Client> = self.clients.iter_mut().chain(others).collect();
+
         // Create a new geometry to rearrange from. This geometry must be the merge result of the self
-        // and the sticky tag.
+        // and the sticky paddings.
         let geometry = TagGeometry::new(
+            self.geo.x,
+            self.geo.y,
             self.geo.w,
             self.geo.h,
             [
-                self.geo.padding_top().max(sticky.geo.padding_top()),
-                self.geo.padding_bottom().max(sticky.geo.padding_bottom()),
-                self.geo.padding_left().max(sticky.geo.padding_left()),
-                self.geo.padding_right().max(sticky.geo.padding_right()),
+                self.geo.padding_top().max(paddings[0]),
+                self.geo.padding_bottom().max(paddings[1]),
+                self.geo.padding_left().max(paddings[2]),
+                self.geo.padding_right().max(paddings[3]),
             ],
         );
 
         // Maximized and fullscreen clients will not be passed to the layout arrange.
-        self.clients
+        clients
             .iter_mut()
             .filter(|c| {
-                (c.get_state() == ClientState::Maximized || c.get_state() == ClientState::Fullscreen) && c.is_controlled()
+                (c.get_state() == ClientState::Maximized || c.get_state() == ClientState::Fullscreen) && c.is_focusable()
             })
             .for_each(|c| {
                 if c.get_state() == ClientState::Maximized {
                     c.geo.border = 0;
                     c.geo.w = geometry.avail_w;
                     c.geo.h = geometry.avail_h;
-                    c.geo.x = geometry.padding_left();
-                    c.geo.y = geometry.padding_top();
+                    c.geo.x = geometry.x + geometry.padding_left();
+                    c.geo.y = geometry.y + geometry.padding_top();
                 } else {
                     c.geo.border = 0;
                     c.geo.w = geometry.w;
                     c.geo.h = geometry.h;
-                    c.geo.x = 0;
-                    c.geo.y = 0;
+                    c.geo.x = geometry.x;
+                    c.geo.y = geometry.y;
                 }
             });
 
         // Only "Tile" clients needs to be passed to the layout arrange.
-        let tiled_clients = &mut self.clients
+        let tiled_clients = &mut clients
             .iter_mut()
-            .filter(|c| c.get_state() == ClientState::Tile && c.is_controlled())
+            .filter(|c| c.get_state() == ClientState::Tile && c.is_focusable())
+            .map(|c| &mut **c)
             .collect::<Vec<&mut Client>>();
 
         let config = Config::current();
@@ -320,10 +513,10 @@ impl Tag {
         if tiled_clients.len() == 1 {
             let c = tiled_clients.get_mut(0).unwrap();
 
-            c.geo.x = config.useless_gap + geometry.padding_left(); 
+            c.geo.x = geometry.x + config.useless_gap + geometry.padding_left(); 
             c.geo.w = geometry.avail_w - (c.geo.border * 2) - (config.useless_gap * 2);
 
-            c.geo.y = config.useless_gap + geometry.padding_top();
+            c.geo.y = geometry.y + config.useless_gap + geometry.padding_top();
             c.geo.h = geometry.avail_h - (c.geo.border * 2) - (config.useless_gap * 2);
 
             c.geo.x = c.geo.x.max(1);
@@ -332,20 +525,8 @@ impl Tag {
             layout.arrange(geometry, config.useless_gap, tiled_clients);
         }
 
-        self.clients
+        clients
             .iter()
-            .for_each(|c| {
-                xcb::configure_window(
-                    &self.conn,
-                    c.id,
-                    &[
-                        (xcb::CONFIG_WINDOW_WIDTH as u16, c.geo.w),
-                        (xcb::CONFIG_WINDOW_HEIGHT as u16, c.geo.h),
-                        (xcb::CONFIG_WINDOW_X as u16, c.geo.x),
-                        (xcb::CONFIG_WINDOW_Y as u16, c.geo.y),
-                        (xcb::CONFIG_WINDOW_BORDER_WIDTH as u16, c.geo.border),
-                    ],
-                );
-            });
+            .for_each(|c| c.configure(&self.conn));
     }
 }
@@ -1,7 +1,16 @@
+use std::{
+    str::FromStr,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
 use xcb_util::ewmh;
 
 use crate::errors::Error;
 
+/// Timestamp of the last user event (key, button, pointer crossing or property change) received
+/// from the X server. See `util::event_time`.
+static EVENT_TIME: AtomicU32 = AtomicU32::new(xcb::CURRENT_TIME);
+
 #[allow(dead_code)]
 pub mod modkeys {
     pub const MODKEY_1: u16 = xcb::MOD_MASK_1 as u16;
@@ -46,6 +55,7 @@ pub mod math {
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum Operation {
     Add,
     Remove,
@@ -67,19 +77,41 @@ where
     }
 }
 
+/// Represents a direction on the screen, used by directional actions such as focusing the monitor
+/// on the left of the focused one.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            _ => Err(Error::Custom(format!("Invalid direction \"{}\".", s))),
+        }
+    }
+}
+
 /// NOTE:
-/// For now, sapphire does not support multiple monitors and due to rust's
-/// lifetimes and how xcb::Screen needs conn, it's really hard to use screen
-/// as an atributte. 
-/// TODO:
-/// support for multiscreen.
+/// Multiple monitors are handled through RandR inside a single X screen (see `monitor::detect`),
+/// so this always returns the first root. Due to rust's lifetimes and how xcb::Screen needs conn,
+/// it's really hard to use screen as an atributte.
 #[inline]
 pub fn get_screen(conn: &xcb::Connection) -> xcb::Screen {
     conn.get_setup().roots().next().unwrap()
 }
 
 #[inline]
-pub fn disable_input_focus(conn: &xcb::Connection) {
+pub fn disable_input_focus(conn: &ewmh::Connection) {
     xcb::set_input_focus(
         conn,
         xcb::INPUT_FOCUS_PARENT as u8,
@@ -87,7 +119,22 @@ pub fn disable_input_focus(conn: &xcb::Connection) {
         xcb::INPUT_FOCUS_POINTER_ROOT,
         xcb::CURRENT_TIME,
     );
-    // TODO: send a None value to active winodw
+    ewmh::set_active_window(conn, 0, xcb::NONE);
+}
+
+/// Records the timestamp of the event being handled. Must be called for every event that carries
+/// a timestamp, before it is handled.
+#[inline(always)]
+pub fn set_event_time(time: xcb::Timestamp) {
+    EVENT_TIME.store(time, Ordering::Relaxed);
+}
+
+/// Returns the timestamp of the event that triggered the current action, to be used where the
+/// ICCCM forbids `xcb::CURRENT_TIME`, such as `WM_TAKE_FOCUS` messages. It is
+/// `xcb::CURRENT_TIME` until the first event with a timestamp is received.
+#[inline(always)]
+pub fn event_time() -> xcb::Timestamp {
+    EVENT_TIME.load(Ordering::Relaxed)
 }
 
 pub fn notify_error(e: String) {
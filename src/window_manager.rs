@@ -8,12 +8,15 @@ use crate::{
         MouseInfo,
     },
     util,
+    config::Config,
     event::{
         Event,
         EventContext,
         MouseEvent,
     },
     action::on_startup::OnStartup,
+    ipc::Ipc,
+    monitor,
     screen::Screen,
     handlers, keyboard::Keyboard,
     keyboard::KeyCombination,
@@ -27,6 +30,21 @@ pub struct WindowManager {
     pub keyboard: Keyboard,
 
     startup_actions: Vec<OnStartup>,
+
+    /// The first event code of the RandR extension, used to identify monitor changes. It is `None`
+    /// when the extension is unavailable.
+    randr_event_base: Option<u8>,
+
+    ipc: Ipc,
+
+    /// The root window of the screen.
+    root: u32,
+
+    /// Sequence number of the first request sent after the last keyboard action. `EnterNotify`
+    /// events generated before it were caused by the action (e.g. a client arranged below the
+    /// pointer) and are ignored, so the focus does not jump away from the client focused with the
+    /// keyboard.
+    ignore_enter_before: u16,
     
     screen: Arc<Mutex<Screen>>,
 }
@@ -41,16 +59,29 @@ impl WindowManager {
         Screen::set_defaults(&conn, 0, 0);
 
         let screen = conn.get_setup().roots().nth(conn_num as usize).unwrap();
-        let screen = Screen::new(conn.clone(), conn_num, screen);
+        // Passing `--replace` replaces the running window manager, if any.
+        let replace = std::env::args().any(|a| a == "--replace");
+
+        let screen = Screen::new(conn.clone(), conn_num, screen, replace).unwrap_or_else(|e| {
+            log::error!("{}", e.to_string());
+            std::process::exit(1)
+        });
         // Configure the cursor of the screen.
         _ = mouse
             .create_cursor(screen.root)
             .map_err(|e| util::notify_error(e.to_string()));
 
+        let randr_event_base = monitor::listen_changes(&conn, screen.root);
+        let root = screen.root;
+
         conn.flush();
 
         WindowManager {
             startup_actions: Vec::new(),
+            randr_event_base,
+            ipc: Ipc::new(&conn),
+            root,
+            ignore_enter_before: 0,
             keyboard: Keyboard::new(conn.clone()),
             screen: Arc::new(Mutex::new(screen)),
             mouse,
@@ -87,17 +118,28 @@ impl WindowManager {
 }
 
 impl WindowManager {
-    fn handle(&self, e: xcb::GenericEvent) {
+    fn handle(&mut self, e: xcb::GenericEvent) {
+        let ctx = EventContext::new(self.conn.clone(), self.screen.clone());
+
+        // Extension events do not have a fixed response type.
+        if self.randr_event_base.is_some_and(|b| monitor::is_change_event(b, e.response_type())) {
+            log::trace!("event received. event_type=RandRNotify");
+            _ = handlers::on_screen_change(ctx);
+            return
+        }
+
         let ev = Event::from(e.response_type());
         log::trace!("event received. event_type={}", ev);
 
-        let ctx = EventContext::new(self.conn.clone(), self.screen.clone());
-
         match ev {
             Event::DestroyNotify => {
                 let e: &xcb::DestroyNotifyEvent = unsafe { xcb::cast_event(&e) };
                 _ = handlers::on_destroy_notify(ctx, e);
             },
+            Event::UnmapNotify => {
+                let e: &xcb::UnmapNotifyEvent = unsafe { xcb::cast_event(&e) };
+                _ = handlers::on_unmap_notify(ctx, e);
+            },
             Event::ClientMessage => {
                 let e: &xcb::ClientMessageEvent = unsafe { xcb::cast_event(&e) };
                 _ = handlers::on_client_message(e, ctx);
@@ -110,20 +152,46 @@ impl WindowManager {
             //     let e: &xcb::ConfigureRequestEvent = unsafe { xcb::cast_event(&e) };
             //     _ = handlers::on_configure_request(e, ctx);
             // },
+            Event::PropertyNotify => {
+                let e: &xcb::PropertyNotifyEvent = unsafe { xcb::cast_event(&e) };
+                util::set_event_time(e.time());
+
+                if self.ipc.is_command(self.root, e) {
+                    _ = handlers::on_ipc_command(ctx, &self.ipc).map_err(|e| util::notify_error(e.to_string()));
+                } else if e.window() != self.root {
+                    _ = handlers::on_property_notify(ctx, e);
+                }
+            },
+            Event::SelectionClear => {
+                let e: &xcb::SelectionClearEvent = unsafe { xcb::cast_event(&e) };
+                handlers::on_selection_clear(ctx, e);
+            },
             Event::MapRequest => {
                 let e: &xcb::MapRequestEvent = unsafe { xcb::cast_event(&e) };
                 _ = handlers::on_map_request(ctx, e);
             },
             Event::KeyPress => {
                 let e: &xcb::KeyPressEvent = unsafe { xcb::cast_event(&e) };
+                util::set_event_time(e.time());
 
                 let mask = KeyCombination { keycode: e.detail(), modifier: e.state() }; 
+                let active = self.active_window();
+
                 _ = self.keyboard
                     .trigger(ctx, mask)
                     .map_err(|e| util::notify_error(e.to_string()));
+
+                self.follow_focus(active);
+            },
+            Event::KeyRelease => {
+                let e: &xcb::KeyReleaseEvent = unsafe { xcb::cast_event(&e) };
+                util::set_event_time(e.time());
+
+                _ = handlers::on_key_release(ctx, e);
             },
             Event::ButtonPress => {
                 let e: &xcb::ButtonPressEvent = unsafe { xcb::cast_event(&e) };
+                util::set_event_time(e.time());
 
                 // We need to free the mouse after retrie the event info.
                 // See: https://www.x.org/releases/current/doc/man/man3/xcb_allow_events.3.xhtml
@@ -136,7 +204,61 @@ impl WindowManager {
                     .trigger_with(MouseEvent::Click, ctx, inf)
                     .map_err(|e| util::notify_error(e.to_string()));
             },
+            Event::EnterNotify => {
+                let e: &xcb::EnterNotifyEvent = unsafe { xcb::cast_event(&e) };
+                util::set_event_time(e.time());
+                let sequence = unsafe { (*e.ptr).sequence };
+
+                // Only the pointer crossing into a client is handled; grabs and moves between a
+                // client and its subwindows are ignored.
+                if e.event() == self.root
+                    || e.mode() != xcb::NOTIFY_MODE_NORMAL as u8
+                    || e.detail() == xcb::NOTIFY_DETAIL_INFERIOR as u8
+                {
+                    return
+                }
+
+                // Sequence numbers wrap around, so they are compared through their difference.
+                if self.ignore_enter_before.wrapping_sub(sequence) as i16 > 0 {
+                    log::trace!("ignoring enter caused by a keyboard action. window={}", e.event());
+                    return
+                }
+
+                let inf = MouseInfo::new(e.event(), e.state(), (e.root_x(), e.root_y()));
+
+                _ = self.mouse
+                    .trigger_with(MouseEvent::Enter, ctx, inf)
+                    .map_err(|e| util::notify_error(e.to_string()));
+            },
             _ => (),
         };
     }
+
+    /// Returns the `_NET_ACTIVE_WINDOW`, or `xcb::NONE` when no client is focused.
+    fn active_window(&self) -> xcb::Window {
+        ewmh::get_active_window(&self.conn, 0)
+            .get_reply()
+            .unwrap_or(xcb::NONE)
+    }
+
+    /// Must be called after a keyboard action, with `previous` being the active window before the
+    /// action. Warps the pointer to the center of the newly focused client when
+    /// `Config::mouse_follows_focus` is set, and marks the `EnterNotify` events caused by the
+    /// action to be ignored.
+    fn follow_focus(&mut self, previous: xcb::Window) {
+        let active = self.active_window();
+
+        if Config::current().mouse_follows_focus && active != previous && active != xcb::NONE {
+            let is_under_pointer = xcb::query_pointer(&self.conn, self.root)
+                .get_reply()
+                .is_ok_and(|p| p.child() == active);
+
+            if let (false, Ok(geo)) = (is_under_pointer, xcb::get_geometry(&self.conn, active).get_reply()) {
+                let (x, y) = ((geo.width() / 2) as i16, (geo.height() / 2) as i16);
+                xcb::warp_pointer(&self.conn, xcb::NONE, active, 0, 0, 0, 0, x, y);
+            }
+        }
+
+        self.ignore_enter_before = xcb::no_operation(&self.conn).cookie.sequence as u16;
+    }
 }